@@ -0,0 +1,45 @@
+// Benchmarks the layout of a large table. Compare the single-threaded measurement with the
+// parallel one by running with and without the rayon feature:
+//
+//   cargo bench --bench measure
+//   cargo bench --bench measure --features rayon
+use criterion::{criterion_group, criterion_main, Criterion};
+use ttygrid::{add_line, grid, header, TTYGrid};
+
+const LINES: usize = 100_000;
+
+fn table() -> TTYGrid {
+    let mut grid = grid!(
+        header!("name"),
+        header!("path"),
+        header!("description"),
+        header!("size")
+    )
+    .unwrap();
+
+    for i in 0..LINES {
+        add_line!(
+            grid,
+            format!("entry-{}", i),
+            format!("/srv/data/ünïcödé/{}/{}", i % 97, i),
+            "é".repeat(i % 40),
+            i * 37
+        );
+    }
+
+    grid
+}
+
+fn display(c: &mut Criterion) {
+    let mut grid = table();
+    let mut group = c.benchmark_group("layout");
+    group.sample_size(10);
+    group.bench_function("display", |b| b.iter(|| grid.display().unwrap()));
+    group.bench_function("minimum_width", |b| {
+        b.iter(|| grid.minimum_width().unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, display);
+criterion_main!(benches);
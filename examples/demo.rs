@@ -9,12 +9,12 @@ const DEFAULT_ROWS: usize = 10;
 use std::env::args;
 
 use crossterm::style::{Color, Colors};
-use ttygrid::{add_line, grid, header};
+use ttygrid::{grid, header, try_add_line};
 
 // this is a handy random string generator I use in a few spots.
 fn randstring(len: u8) -> String {
     (0..len)
-        .map(|_| (rand::random::<u8>() % 26) + 'a' as u8)
+        .map(|_| (rand::random::<u8>() % 26) + b'a')
         .map(|c| {
             if rand::random::<bool>() {
                 (c as char).to_ascii_uppercase()
@@ -29,7 +29,7 @@ fn randstring(len: u8) -> String {
 
 // small func to min/max random strings
 fn rando(max_len: u8, min_len: u8) -> String {
-    format!("{}", randstring(rand::random::<u8>() % max_len + min_len))
+    randstring(rand::random::<u8>() % max_len + min_len)
 }
 
 fn main() -> Result<(), anyhow::Error> {
@@ -87,10 +87,11 @@ fn main() -> Result<(), anyhow::Error> {
         header_five
     )?;
 
-    // the add_line! macro lines up your content to the position in the grid. the rando() function
-    // here just generates a random string.
+    // the try_add_line! macro lines up your content to the position in the grid, yielding an
+    // error if the number of items is wrong (add_line! panics instead). the rando() function here
+    // just generates a random string.
     for lineno in 0..rows {
-        add_line!(
+        try_add_line!(
             g,
             format!("{}", lineno),
             rando(max_len, min_len),
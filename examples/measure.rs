@@ -0,0 +1,39 @@
+// Times the layout of a large table. Compare the single-threaded measurement with the parallel
+// one by running with and without the rayon feature:
+//
+//   cargo run --release --example measure
+//   cargo run --release --example measure --features rayon
+use std::time::Instant;
+use ttygrid::{add_line, grid, header};
+
+const LINES: usize = 1_000_000;
+
+fn main() -> anyhow::Result<()> {
+    let mut grid = grid!(
+        header!("name"),
+        header!("path"),
+        header!("description"),
+        header!("size")
+    )?;
+
+    for i in 0..LINES {
+        add_line!(
+            grid,
+            format!("entry-{}", i),
+            format!("/srv/data/ünïcödé/{}/{}", i % 97, i),
+            "é".repeat(i % 40),
+            i * 37
+        );
+    }
+
+    let start = Instant::now();
+    let output = grid.display()?;
+    println!(
+        "laid out {} lines ({} bytes) in {:?}",
+        LINES,
+        output.len(),
+        start.elapsed()
+    );
+
+    Ok(())
+}
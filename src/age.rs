@@ -0,0 +1,54 @@
+use std::{
+    fmt,
+    time::{Duration, SystemTime},
+};
+
+/// AgeCell is the contents of an item which displays how long ago a time was, such as "5m" or
+/// "2h". The age is computed every time the grid is displayed rather than when the item is
+/// added, so tables which are written repeatedly stay correct. Create items with
+/// [crate::GridItem::age].
+///
+/// ```
+/// use std::time::{Duration, SystemTime};
+/// use ttygrid::AgeCell;
+///
+/// let now = SystemTime::now();
+/// let cell = AgeCell::new(now - Duration::from_secs(300));
+/// assert_eq!(cell.render_at(now), "5m");
+/// assert_eq!(AgeCell::format(Duration::from_secs(2 * 86_400 + 5)), "2d");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AgeCell(SystemTime);
+
+impl AgeCell {
+    pub fn new(time: SystemTime) -> Self {
+        Self(time)
+    }
+
+    pub fn time(&self) -> SystemTime {
+        self.0
+    }
+
+    /// Render the age as of now. Times in the future are displayed as "0s".
+    pub fn render_at(&self, now: SystemTime) -> String {
+        Self::format(now.duration_since(self.0).unwrap_or_default())
+    }
+
+    /// Format a duration in its largest whole unit: seconds, minutes, hours or days.
+    pub fn format(duration: Duration) -> String {
+        let secs = duration.as_secs();
+
+        match secs {
+            0..=59 => format!("{}s", secs),
+            60..=3_599 => format!("{}m", secs / 60),
+            3_600..=86_399 => format!("{}h", secs / 3_600),
+            _ => format!("{}d", secs / 86_400),
+        }
+    }
+}
+
+impl fmt::Display for AgeCell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render_at(SystemTime::now()))
+    }
+}
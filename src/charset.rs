@@ -0,0 +1,69 @@
+/// Charset selects the characters used for tree guides, sparklines, clipped rows and the
+/// [crate::Pager]'s scroll indicators; see [crate::TTYGrid::set_charset]. Ascii is meant for
+/// legacy terminals and log files which can not display UTF-8.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Charset {
+    #[default]
+    Unicode,
+    Ascii,
+}
+
+impl Charset {
+    /// The guide of a tree line with a sibling below it.
+    pub fn tree_branch(&self) -> &'static str {
+        match self {
+            Charset::Unicode => "├─ ",
+            Charset::Ascii => "|- ",
+        }
+    }
+
+    /// The guide of the last line of a tree level.
+    pub fn tree_last(&self) -> &'static str {
+        match self {
+            Charset::Unicode => "└─ ",
+            Charset::Ascii => "`- ",
+        }
+    }
+
+    /// The guide of a tree level which continues below the current line.
+    pub fn tree_pipe(&self) -> &'static str {
+        match self {
+            Charset::Unicode => "│  ",
+            Charset::Ascii => "|  ",
+        }
+    }
+
+    /// The marker at the end of clipped contents.
+    pub fn ellipsis(&self) -> &'static str {
+        match self {
+            Charset::Unicode => "…",
+            Charset::Ascii => "...",
+        }
+    }
+
+    /// Arrows pointing left and right.
+    pub fn arrows(&self) -> (&'static str, &'static str) {
+        match self {
+            Charset::Unicode => ("←", "→"),
+            Charset::Ascii => ("<", ">"),
+        }
+    }
+
+    /// Arrows pointing up and down, marking columns sorted in ascending and descending order.
+    pub fn sort_arrows(&self) -> (&'static str, &'static str) {
+        match self {
+            Charset::Unicode => ("▲", "▼"),
+            Charset::Ascii => ("^", "v"),
+        }
+    }
+
+    /// The levels of a sparkline or gauge, lowest first.
+    pub fn gauge(&self) -> &'static [char; 8] {
+        match self {
+            Charset::Unicode => &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'],
+            Charset::Ascii => &['_', '.', ':', '-', '=', '+', '*', '#'],
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use crate::GridLine;
+
+/// Change describes how a cell differs from the same cell of another grid; see
+/// [crate::TTYGrid::diff].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change {
+    /// The cell has the same contents.
+    Same,
+    /// The cell has different contents.
+    Changed,
+    /// The cell does not exist in the other grid.
+    Added,
+}
+
+/// GridDiff holds the changes to each cell of a grid, compared to another grid. Cells are
+/// compared by position: the line number and the column number, in the order the headers are
+/// defined.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GridDiff {
+    lines: Vec<Vec<Change>>,
+    removed_lines: usize,
+}
+
+impl GridDiff {
+    pub(crate) fn new(current: &[GridLine], previous: &[GridLine]) -> Self {
+        let lines = current
+            .iter()
+            .enumerate()
+            .map(|(idx, line)| {
+                line.iter()
+                    .enumerate()
+                    .map(
+                        |(col, item)| match previous.get(idx).and_then(|l| l.get(col)) {
+                            Some(prev) if prev.contents() == item.contents() => Change::Same,
+                            Some(_) => Change::Changed,
+                            None => Change::Added,
+                        },
+                    )
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            lines,
+            removed_lines: previous.len().saturating_sub(current.len()),
+        }
+    }
+
+    /// Yield the change to the cell at the line and column. Cells which do not exist are
+    /// [Change::Same].
+    pub fn change(&self, line: usize, column: usize) -> Change {
+        self.lines
+            .get(line)
+            .and_then(|l| l.get(column))
+            .copied()
+            .unwrap_or(Change::Same)
+    }
+
+    /// The number of cells which were changed or added.
+    pub fn changed_cells(&self) -> usize {
+        self.lines
+            .iter()
+            .flatten()
+            .filter(|c| **c != Change::Same)
+            .count()
+    }
+
+    /// The number of lines the other grid has beyond the end of this one.
+    pub fn removed_lines(&self) -> usize {
+        self.removed_lines
+    }
+
+    /// True if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.removed_lines == 0 && self.changed_cells() == 0
+    }
+}
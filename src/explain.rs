@@ -0,0 +1,124 @@
+use crate::{TTYGrid, TerminalTooSmall};
+use anyhow::Result;
+use std::{fmt::Write, rc::Rc};
+
+impl TTYGrid {
+    /// Lay out the grid like [TTYGrid::display] and describe the choices made, one line for the
+    /// terminal and one for each column: whether it was shown, and with what priority and width,
+    /// or why it was left out. This is meant for tuning the priorities and widths of columns, not
+    /// for display to users; the wording may change.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, test::FakeTerm, Priority};
+    ///
+    /// let mut grid = grid!(
+    ///     header!("name", Priority::Essential),
+    ///     header!("notes", Priority::Debug)
+    /// )
+    /// .unwrap();
+    /// grid.refresh_width(&FakeTerm::new(20, 10)).unwrap();
+    /// add_line!(grid, "web-1", "restarted twice overnight");
+    ///
+    /// let explanation = grid.explain_layout().unwrap();
+    /// assert!(explanation.contains("column 'name' shown"));
+    /// let notes = "column 'notes' dropped: priority 0, needed 32 cols, only 8 free";
+    /// assert!(explanation.contains(notes));
+    /// ```
+    pub fn explain_layout(&mut self) -> Result<String> {
+        let mut ret = String::new();
+
+        match self.determine_headers() {
+            Ok(()) => writeln!(
+                ret,
+                "terminal width {} cols, table width {} cols",
+                self.width,
+                self.total_width()
+            )?,
+            Err(e) => match e.downcast_ref::<TerminalTooSmall>() {
+                Some(too_small) => writeln!(ret, "{}", too_small)?,
+                None => return Err(e),
+            },
+        }
+
+        let candidates = self.candidates();
+        let free = self.width.saturating_sub(self.total_width());
+
+        for (idx, header) in self.headers.0.iter().enumerate() {
+            let h = header.borrow();
+            write!(ret, "column '{}' ", h.key())?;
+
+            if self.hidden_index(idx) {
+                writeln!(ret, "hidden")?;
+                continue;
+            }
+
+            if let (Some(view), Some(name)) = (self.view(), &self.active_view) {
+                if !view.contains(&idx) {
+                    writeln!(ret, "hidden: not in view '{}'", name)?;
+                    continue;
+                }
+            }
+
+            if self.hidden_uninformative(idx) {
+                writeln!(ret, "hidden: its items are all empty or the same")?;
+                continue;
+            }
+
+            if !candidates.contains(&idx) {
+                writeln!(ret, "scrolled out of view")?;
+                continue;
+            }
+
+            let priority = self.layout_priority(idx);
+            let width = h.max_len.unwrap_or_default();
+            let mut notes = Vec::new();
+            if self.deprioritized(idx) {
+                notes.push(", deprioritized as its items are all empty or the same".to_string());
+            }
+
+            if self.selected.iter().any(|h| Rc::ptr_eq(h, header)) {
+                if self.frozen.contains(&idx) {
+                    notes.push(", frozen".to_string());
+                }
+                writeln!(
+                    ret,
+                    "shown: priority {}, {} cols{}",
+                    priority,
+                    width,
+                    notes.concat()
+                )?;
+                continue;
+            }
+
+            let table = self
+                .overflow_tables
+                .iter()
+                .position(|table| table.iter().any(|h| Rc::ptr_eq(h, header)));
+            if let Some(table) = table {
+                notes.push(format!(", stacked in table {}", table + 2));
+            }
+
+            if width > free {
+                writeln!(
+                    ret,
+                    "dropped: priority {}, needed {} cols, only {} free{}",
+                    priority,
+                    width,
+                    free,
+                    notes.concat()
+                )?;
+            } else {
+                writeln!(
+                    ret,
+                    "dropped: priority {}, needed {} cols, a layout of higher priority without it \
+                     was preferred{}",
+                    priority,
+                    width,
+                    notes.concat()
+                )?;
+            }
+        }
+
+        Ok(ret)
+    }
+}
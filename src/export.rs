@@ -0,0 +1,417 @@
+use crate::{GridLine, HeaderList, TTYGrid};
+use anyhow::Result;
+use std::{borrow::Cow, io::Write};
+
+/// ExportFormat is a machine or document readable format a grid can be exported to; see
+/// [TTYGrid::export].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Tsv,
+    /// An array with an object for each line, keyed by [crate::GridHeader::key].
+    Json,
+    /// JSON Lines: an object for each line, like [ExportFormat::Json], one per line of output.
+    /// See [TTYGrid::jsonl_writer] for writing lines as they are produced.
+    JsonLines,
+    Markdown,
+}
+
+impl ExportFormat {
+    /// The columns exported unless specified otherwise. Machine readable formats include every
+    /// column, so data is not silently missing because the terminal was narrow; Markdown, which
+    /// is meant to be read, includes the columns which would be displayed.
+    pub fn default_columns(&self) -> ExportColumns {
+        match self {
+            ExportFormat::Csv
+            | ExportFormat::Tsv
+            | ExportFormat::Json
+            | ExportFormat::JsonLines => ExportColumns::All,
+            ExportFormat::Markdown => ExportColumns::Displayed,
+        }
+    }
+}
+
+/// QuoteStyle controls how fields of CSV and TSV exports are quoted; see
+/// [TTYGrid::set_quote_style]. JSON is always escaped as it requires.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum QuoteStyle {
+    /// Quote the fields which contain the delimiter, a quote or a line break, doubling the quotes
+    /// within them, as RFC 4180 describes.
+    #[default]
+    Minimal,
+    /// Quote every field, including the labels.
+    Always,
+    /// Quote no field, writing tabs, line breaks and backslashes as `\t`, `\n`, `\r` and `\\`
+    /// instead, and the commas of CSV fields as `\,`. Many TSV readers, such as databases
+    /// loading text files, expect this.
+    Escape,
+}
+
+/// ExportColumns controls which columns [TTYGrid::export_with] includes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportColumns {
+    /// The columns which fit the terminal, as laid out for display, and the lines which are
+    /// displayed: those which are not hidden, or filtered out (see [TTYGrid::set_filter]).
+    Displayed,
+    /// Every column of the grid, including hidden ones and those outside the active view, and
+    /// every line, including hidden ones (see [TTYGrid::set_line_hidden]) and those which do not
+    /// match the filters.
+    All,
+}
+
+impl TTYGrid {
+    /// Export the lines of the grid to the writer in the format, with the format's default
+    /// columns; see [ExportFormat::default_columns]. The contents of the items are exported
+    /// without any display options such as deduplication.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, ExportFormat};
+    ///
+    /// let mut grid = grid!(header!("name"), header!("size")).unwrap();
+    /// add_line!(grid, "a, b", 10);
+    ///
+    /// let mut csv = Vec::new();
+    /// grid.export(&mut csv, ExportFormat::Csv).unwrap();
+    /// assert_eq!(String::from_utf8(csv).unwrap(), "name,size\n\"a, b\",10\n");
+    ///
+    /// let mut json = Vec::new();
+    /// grid.export(&mut json, ExportFormat::Json).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(json).unwrap(),
+    ///     "[{\"name\":\"a, b\",\"size\":\"10\"}]\n"
+    /// );
+    /// ```
+    pub fn export(&mut self, writer: impl Write, format: ExportFormat) -> Result<()> {
+        self.export_with(writer, format, format.default_columns())
+    }
+
+    /// Export the lines of the grid like [TTYGrid::export], selecting the columns to include.
+    pub fn export_with(
+        &mut self,
+        mut writer: impl Write,
+        format: ExportFormat,
+        columns: ExportColumns,
+    ) -> Result<()> {
+        let headers = match columns {
+            ExportColumns::Displayed => {
+                self.determine_headers()?;
+                self.selected.clone()
+            }
+            ExportColumns::All => self.headers.clone(),
+        };
+        self.materialize(&headers);
+
+        let rows = self.export_rows(&headers, columns == ExportColumns::All);
+        let rows = rows
+            .iter()
+            .map(|row| row.iter().map(|c| c.as_ref()).collect())
+            .collect::<Vec<Vec<&str>>>();
+        let labels = headers
+            .iter()
+            .map(|h| self.label(&h.borrow()))
+            .collect::<Vec<String>>();
+
+        let quote = self.quote_style;
+        match format {
+            ExportFormat::Csv => write_delimited(&mut writer, ',', quote, &labels, &rows)?,
+            ExportFormat::Tsv => write_delimited(&mut writer, '\t', quote, &labels, &rows)?,
+            ExportFormat::Markdown => write_markdown(&mut writer, &labels, &rows)?,
+            ExportFormat::Json | ExportFormat::JsonLines => {
+                let keys = headers
+                    .iter()
+                    .map(|h| h.borrow().key().to_string())
+                    .collect::<Vec<String>>();
+                if format == ExportFormat::Json {
+                    write_json(&mut writer, &keys, &rows)?
+                } else {
+                    for row in rows.iter() {
+                        writeln!(writer, "{}", json_object(&keys, row))?;
+                    }
+                }
+            }
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Select how fields of CSV and TSV exports are quoted. The default is
+    /// [QuoteStyle::Minimal].
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, ExportFormat, QuoteStyle};
+    ///
+    /// let mut grid = grid!(header!("name"), header!("notes")).unwrap();
+    /// add_line!(grid, "a", "two\tcolumns\nand lines");
+    ///
+    /// let mut tsv = Vec::new();
+    /// grid.export(&mut tsv, ExportFormat::Tsv).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(tsv).unwrap(),
+    ///     "name\tnotes\na\t\"two\tcolumns\nand lines\"\n"
+    /// );
+    ///
+    /// grid.set_quote_style(QuoteStyle::Escape);
+    /// let mut tsv = Vec::new();
+    /// grid.export(&mut tsv, ExportFormat::Tsv).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(tsv).unwrap(),
+    ///     "name\tnotes\na\ttwo\\tcolumns\\nand lines\n"
+    /// );
+    /// ```
+    pub fn set_quote_style(&mut self, quote: QuoteStyle) {
+        self.quote_style = quote
+    }
+
+    pub fn quote_style(&self) -> QuoteStyle {
+        self.quote_style
+    }
+
+    /// Export every line of the grid to the writer as JSON Lines; see [ExportFormat::JsonLines].
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header};
+    ///
+    /// let mut grid = grid!(header!("name"), header!("notes")).unwrap();
+    /// add_line!(grid, "web-1", "say \"hi\"");
+    /// add_line!(grid, "web-2", "");
+    ///
+    /// let mut jsonl = Vec::new();
+    /// grid.write_jsonl(&mut jsonl).unwrap();
+    /// let jsonl = String::from_utf8(jsonl).unwrap();
+    /// assert_eq!(
+    ///     jsonl.lines().collect::<Vec<_>>(),
+    ///     vec![
+    ///         r#"{"name":"web-1","notes":"say \"hi\""}"#,
+    ///         r#"{"name":"web-2","notes":""}"#,
+    ///     ]
+    /// );
+    /// ```
+    pub fn write_jsonl(&mut self, writer: impl Write) -> Result<()> {
+        self.export(writer, ExportFormat::JsonLines)
+    }
+
+    /// Yield a writer which writes lines as JSON Lines as soon as they are given to it, without
+    /// adding them to the grid, so a program can stream its output to a pipeline with the same
+    /// grid definition it displays to people. Every column is written, keyed by
+    /// [crate::GridHeader::key], with the transforms and redactions of the grid applied.
+    ///
+    /// ```
+    /// use ttygrid::{grid, header};
+    ///
+    /// let grid = grid!(header!("name"), header!("size")).unwrap();
+    /// let mut jsonl = grid.jsonl_writer(Vec::new());
+    /// for (name, size) in [("a", 10), ("b", 20)] {
+    ///     jsonl.write_contents(vec![name.to_string(), size.to_string()]).unwrap();
+    /// }
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(jsonl.into_inner()).unwrap(),
+    ///     "{\"name\":\"a\",\"size\":\"10\"}\n{\"name\":\"b\",\"size\":\"20\"}\n"
+    /// );
+    /// ```
+    pub fn jsonl_writer<W: Write>(&self, writer: W) -> JsonLinesWriter<'_, W> {
+        JsonLinesWriter {
+            keys: self
+                .headers
+                .iter()
+                .map(|h| h.borrow().key().to_string())
+                .collect(),
+            grid: self,
+            writer,
+        }
+    }
+
+    // yields the contents of the headers' columns for every line in display order, skipping
+    // hidden and filtered out lines unless asked for. Lines without an item for a header yield
+    // an empty string. Redacted columns yield the redacted contents.
+    fn export_rows(&self, headers: &HeaderList, hidden: bool) -> Vec<Vec<Cow<'_, str>>> {
+        let lines = self
+            .line_order()
+            .filter(|idx| hidden || self.line_shown(*idx))
+            .map(|idx| &self.lines[idx]);
+        self.export_rows_of(headers, lines)
+    }
+
+    // yields the contents of the headers' columns for each of the lines.
+    fn export_rows_of<'a>(
+        &self,
+        headers: &HeaderList,
+        lines: impl Iterator<Item = &'a GridLine>,
+    ) -> Vec<Vec<Cow<'a, str>>> {
+        lines
+            .map(|line| {
+                headers
+                    .iter()
+                    .map(|header| {
+                        let contents = line
+                            .items
+                            .iter()
+                            .find(|item| std::rc::Rc::ptr_eq(&item.header, header))
+                            .map(|item| item.contents.as_str())
+                            .unwrap_or_default();
+                        self.redacted(header, contents)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+fn write_delimited(
+    writer: &mut impl Write,
+    delimiter: char,
+    quote: QuoteStyle,
+    labels: &[String],
+    rows: &[Vec<&str>],
+) -> Result<()> {
+    let quoted = |text: &str| format!("\"{}\"", text.replace('"', "\"\""));
+    let field = |text: &str| match quote {
+        QuoteStyle::Minimal if text.contains(delimiter) || text.contains(['"', '\n', '\r']) => {
+            quoted(text)
+        }
+        QuoteStyle::Minimal => text.to_string(),
+        QuoteStyle::Always => quoted(text),
+        QuoteStyle::Escape => escaped(text, delimiter),
+    };
+
+    let labels = labels.iter().map(|l| l.as_str());
+    for row in std::iter::once(labels.collect::<Vec<&str>>()).chain(rows.iter().cloned()) {
+        let fields = row.into_iter().map(field).collect::<Vec<String>>();
+        writeln!(writer, "{}", fields.join(&delimiter.to_string()))?;
+    }
+
+    Ok(())
+}
+
+// escapes the text for a delimited field without quotes.
+fn escaped(text: &str, delimiter: char) -> String {
+    let mut ret = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '\\' => ret.push_str("\\\\"),
+            '\t' => ret.push_str("\\t"),
+            '\n' => ret.push_str("\\n"),
+            '\r' => ret.push_str("\\r"),
+            c if c == delimiter => {
+                ret.push('\\');
+                ret.push(c)
+            }
+            c => ret.push(c),
+        }
+    }
+
+    ret
+}
+
+fn write_markdown(writer: &mut impl Write, labels: &[String], rows: &[Vec<&str>]) -> Result<()> {
+    let cell = |text: &str| {
+        text.replace('|', "\\|")
+            .replace("\r\n", "<br>")
+            .replace(['\n', '\r'], "<br>")
+    };
+
+    let labels = labels.iter().map(|l| cell(l)).collect::<Vec<String>>();
+    writeln!(writer, "| {} |", labels.join(" | "))?;
+    writeln!(writer, "|{}", " --- |".repeat(labels.len()))?;
+
+    for row in rows {
+        let cells = row.iter().map(|c| cell(c)).collect::<Vec<String>>();
+        writeln!(writer, "| {} |", cells.join(" | "))?;
+    }
+
+    Ok(())
+}
+
+/// JsonLinesWriter writes lines of a grid as JSON Lines as they are given to it; see
+/// [TTYGrid::jsonl_writer].
+pub struct JsonLinesWriter<'a, W: Write> {
+    grid: &'a TTYGrid,
+    keys: Vec<String>,
+    writer: W,
+}
+
+impl<W: Write> JsonLinesWriter<'_, W> {
+    /// Write the line, which is usually made with [TTYGrid::line_from].
+    pub fn write_line(&mut self, line: GridLine) -> Result<()> {
+        let mut line = line.transformed();
+        for item in line.items.iter_mut() {
+            item.materialize();
+        }
+
+        let row = self
+            .grid
+            .export_rows_of(&self.grid.headers, std::iter::once(&line))
+            .pop()
+            .unwrap_or_default();
+        let row = row.iter().map(|c| c.as_ref()).collect::<Vec<&str>>();
+
+        writeln!(self.writer, "{}", json_object(&self.keys, &row))?;
+        Ok(())
+    }
+
+    /// Write a line from its contents, in the order of the headers, with the same rules as
+    /// [TTYGrid::add_contents].
+    pub fn write_contents(&mut self, contents: Vec<String>) -> Result<()> {
+        let line = self.grid.line_from(contents)?;
+        self.write_line(line)
+    }
+
+    /// Flush the writer.
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Yield the writer, once all lines are written.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+fn write_json(writer: &mut impl Write, keys: &[String], rows: &[Vec<&str>]) -> Result<()> {
+    write!(writer, "[")?;
+
+    for (idx, row) in rows.iter().enumerate() {
+        if idx > 0 {
+            write!(writer, ",")?;
+        }
+
+        write!(writer, "{}", json_object(keys, row))?;
+    }
+
+    writeln!(writer, "]")?;
+    Ok(())
+}
+
+fn json_object(keys: &[String], row: &[&str]) -> String {
+    let fields = keys
+        .iter()
+        .zip(row)
+        .map(|(key, value)| format!("{}:{}", json_string(key), json_string(value)))
+        .collect::<Vec<String>>();
+    format!("{{{}}}", fields.join(","))
+}
+
+fn json_string(text: &str) -> String {
+    let mut ret = String::with_capacity(text.len() + 2);
+    ret.push('"');
+
+    for c in text.chars() {
+        match c {
+            '"' => ret.push_str("\\\""),
+            '\\' => ret.push_str("\\\\"),
+            '\n' => ret.push_str("\\n"),
+            '\r' => ret.push_str("\\r"),
+            '\t' => ret.push_str("\\t"),
+            c if (c as u32) < 0x20 => ret.push_str(&format!("\\u{:04x}", c as u32)),
+            c => ret.push(c),
+        }
+    }
+
+    ret.push('"');
+    ret
+}
@@ -0,0 +1,103 @@
+use crate::{SafeGridHeader, TTYGrid};
+use anyhow::Result;
+use std::rc::Rc;
+
+/// FilterDisplay controls how lines which do not match the filters of a grid are displayed; see
+/// [TTYGrid::set_filter].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum FilterDisplay {
+    /// Leave the lines out, like hidden lines (see [TTYGrid::set_line_hidden]).
+    #[default]
+    Hide,
+    /// Keep the lines, so the matches can be seen in context, and write them dimmed. The lines
+    /// are only dimmed by [TTYGrid::write] and the [crate::Pager]; other output displays them as
+    /// usual.
+    Dim,
+}
+
+impl TTYGrid {
+    /// Filter the lines of the grid to those whose item in the header's column contains the text,
+    /// ignoring case. Each column holds one filter, and a line must match all of them. Redacted
+    /// columns (see [TTYGrid::set_redaction]) are matched against their redacted contents, and
+    /// lazy items (see [TTYGrid::add_lazy_line]) only once they have been produced.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, FilterDisplay};
+    ///
+    /// let name = header!("name");
+    /// let mut grid = grid!(name.clone()).unwrap();
+    /// add_line!(grid, "web-1");
+    /// add_line!(grid, "db-1");
+    /// add_line!(grid, "Web-2");
+    /// grid.set_filter(name, "web").unwrap();
+    ///
+    /// let output = grid.display().unwrap();
+    /// assert_eq!(output.lines().skip(2).collect::<Vec<&str>>(), vec!["web-1", "Web-2"]);
+    ///
+    /// grid.set_filter_display(FilterDisplay::Dim);
+    /// assert_eq!(grid.display().unwrap().lines().count(), 5);
+    /// assert!(!grid.matches_filters(1));
+    /// ```
+    pub fn set_filter(&mut self, header: SafeGridHeader, text: impl Into<String>) -> Result<()> {
+        let idx = self.header_index(&header)?;
+        self.filters.retain(|(i, _)| *i != idx);
+        self.filters.push((idx, text.into().to_lowercase()));
+        Ok(())
+    }
+
+    /// Remove the filter of the header's column.
+    pub fn clear_filter(&mut self, header: SafeGridHeader) -> Result<()> {
+        let idx = self.header_index(&header)?;
+        self.filters.retain(|(i, _)| *i != idx);
+        Ok(())
+    }
+
+    /// Remove all filters.
+    pub fn clear_filters(&mut self) {
+        self.filters.clear()
+    }
+
+    /// Select how lines which do not match the filters are displayed. The default is
+    /// [FilterDisplay::Hide].
+    pub fn set_filter_display(&mut self, display: FilterDisplay) {
+        self.filter_display = display
+    }
+
+    pub fn filter_display(&self) -> FilterDisplay {
+        self.filter_display
+    }
+
+    /// Whether the line at the position matches every filter. Lines match when there are no
+    /// filters.
+    pub fn matches_filters(&self, idx: usize) -> bool {
+        let line = match self.lines.get(idx) {
+            Some(line) => line,
+            None => return false,
+        };
+
+        self.filters.iter().all(|(header_idx, text)| {
+            let header = &self.headers.0[*header_idx];
+            let contents = line
+                .items
+                .iter()
+                .find(|item| Rc::ptr_eq(&item.header, header))
+                .map(|item| self.redacted(header, &item.contents).to_lowercase())
+                .unwrap_or_default();
+            contents.contains(text.as_str())
+        })
+    }
+
+    // whether the line at the position is displayed: it is not hidden, and it matches the
+    // filters unless non-matching lines are dimmed.
+    pub(crate) fn line_shown(&self, idx: usize) -> bool {
+        !self.lines[idx].hidden
+            && (self.filter_display == FilterDisplay::Dim || self.matches_filters(idx))
+    }
+
+    // whether the line at the position is displayed dimmed.
+    pub(crate) fn line_dimmed(&self, idx: usize) -> bool {
+        self.filter_display == FilterDisplay::Dim && !self.matches_filters(idx)
+    }
+}
@@ -0,0 +1,155 @@
+use crate::{Charset, SafeGridHeader, TTYGrid, Width};
+use anyhow::Result;
+
+/// IconColumn turns a column of enum-like values, such as service states, into single glyph
+/// icons, for compact status displays on narrow terminals. Each value has an icon, which should
+/// take two cells of the terminal like most emoji do, and an ASCII fallback of at most two
+/// characters, which is displayed instead when the grid's charset is [Charset::Ascii] (see
+/// [TTYGrid::set_charset]). Values are recognized regardless of case and surrounding
+/// whitespace; other values are displayed as they are, clipped to two characters. See
+/// [TTYGrid::set_icon_column].
+///
+/// By default `ok`, `up`, `running`, `healthy` and `true` are 🟢 (`+`); `warn`, `warning`,
+/// `degraded` and `pending` are 🟡 (`~`); `error`, `failed`, `down`, `stopped` and `false` are
+/// 🔴 (`x`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IconColumn {
+    icons: Vec<(String, String, String)>,
+}
+
+impl Default for IconColumn {
+    fn default() -> Self {
+        let icons: [(&[&str], &str, &str); 3] = [
+            (&["ok", "up", "running", "healthy", "true"], "🟢", "+"),
+            (&["warn", "warning", "degraded", "pending"], "🟡", "~"),
+            (&["error", "failed", "down", "stopped", "false"], "🔴", "x"),
+        ];
+
+        let mut ret = Self::empty();
+        for (values, icon, fallback) in icons.iter() {
+            for value in values.iter() {
+                ret = ret.set_icon(value, icon, fallback);
+            }
+        }
+        ret
+    }
+}
+
+impl IconColumn {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An icon column which knows no values, to be filled with [IconColumn::set_icon].
+    pub fn empty() -> Self {
+        Self { icons: Vec::new() }
+    }
+
+    /// Set the icon of the value and its ASCII fallback, replacing any it had. The fallback is
+    /// clipped to its first two characters.
+    ///
+    /// ```
+    /// use ttygrid::{Charset, IconColumn};
+    ///
+    /// let icons = IconColumn::empty().set_icon("done", "✅", "ok!");
+    /// assert_eq!(icons.icon("done", Charset::Unicode), Some("✅"));
+    /// assert_eq!(icons.icon("done", Charset::Ascii), Some("ok"));
+    /// ```
+    pub fn set_icon(mut self, value: &str, icon: &str, fallback: &str) -> Self {
+        let value = value.trim().to_lowercase();
+        let fallback = fallback.chars().take(2).collect();
+        self.icons.retain(|(v, _, _)| *v != value);
+        self.icons.push((value, icon.to_string(), fallback));
+        self
+    }
+
+    /// The icon of the value in the charset, if it has one.
+    pub fn icon(&self, value: &str, charset: Charset) -> Option<&str> {
+        let value = value.trim().to_lowercase();
+        self.icons
+            .iter()
+            .find(|(v, _, _)| *v == value)
+            .map(|(_, icon, fallback)| match charset {
+                Charset::Unicode => icon.as_str(),
+                Charset::Ascii => fallback.as_str(),
+            })
+    }
+}
+
+impl TTYGrid {
+    /// Display the header's column as an [IconColumn]. The column is two characters wide (see
+    /// [Width::Chars]) without padding, and like marker columns (see
+    /// [TTYGrid::set_marker_column]) it is laid out with the priority of
+    /// [crate::Priority::Essential] columns, so it is never dropped for other columns, and does
+    /// not count towards the narrowest layout. Only the display is changed; exports write the
+    /// values.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, test::check_layout, Charset, IconColumn};
+    ///
+    /// let state = header!("");
+    /// let mut grid = grid!(state.clone(), header!("name")).unwrap();
+    /// grid.set_icon_column(state, IconColumn::new()).unwrap();
+    /// add_line!(grid, "running", "web-1");
+    /// add_line!(grid, "Stopped", "web-2");
+    ///
+    /// let output = grid.display().unwrap();
+    /// assert!(output.contains("🟢   web-1"));
+    /// check_layout(&mut grid).unwrap();
+    ///
+    /// grid.set_charset(Charset::Ascii);
+    /// let output = grid.display().unwrap();
+    /// assert!(output.contains("x    web-2"));
+    /// ```
+    pub fn set_icon_column(&mut self, header: SafeGridHeader, icons: IconColumn) -> Result<()> {
+        let idx = self.header_index(&header)?;
+        let saved = match self.icons.iter().position(|(i, _, _)| *i == idx) {
+            Some(pos) => self.icons.remove(pos).2,
+            None => {
+                let mut header = header.borrow_mut();
+                let saved = (header.width, header.max_pad);
+                header.width = Some(Width::Chars(2));
+                header.max_pad = Some(0);
+                saved
+            }
+        };
+        self.icons.push((idx, icons, saved));
+        Ok(())
+    }
+
+    /// Display the header's column like any other, with the width and padding it had before it
+    /// was made an icon column.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, IconColumn};
+    ///
+    /// let state = header!("state");
+    /// let mut grid = grid!(state.clone(), header!("name")).unwrap();
+    /// add_line!(grid, "running", "web-1");
+    /// let before = grid.display().unwrap();
+    ///
+    /// grid.set_icon_column(state.clone(), IconColumn::new()).unwrap();
+    /// assert_ne!(grid.display().unwrap(), before);
+    ///
+    /// grid.clear_icon_column(state).unwrap();
+    /// assert_eq!(grid.display().unwrap(), before);
+    /// ```
+    pub fn clear_icon_column(&mut self, header: SafeGridHeader) -> Result<()> {
+        let idx = self.header_index(&header)?;
+        if let Some(pos) = self.icons.iter().position(|(i, _, _)| *i == idx) {
+            let (width, max_pad) = self.icons.remove(pos).2;
+            let mut header = header.borrow_mut();
+            header.width = width;
+            header.max_pad = max_pad;
+        }
+        Ok(())
+    }
+
+    // the icon column at the position, if it is one.
+    pub(crate) fn icon_column(&self, idx: usize) -> Option<&IconColumn> {
+        self.icons
+            .iter()
+            .find(|(i, _, _)| *i == idx)
+            .map(|(_, icons, _)| icons)
+    }
+}
@@ -0,0 +1,845 @@
+use crate::{write_clipped, GridLine, Row, Segments, TTYGrid};
+use anyhow::Result;
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute,
+    style::{Attribute, Color, Colors, ResetColor, SetAttribute},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use std::{
+    io::Write,
+    time::{Duration, Instant},
+};
+
+/// Pager displays a [TTYGrid] interactively in the alternate screen of the terminal. The table is
+/// laid out for the terminal's current size every time it is drawn.
+///
+/// Keys:
+///
+/// - up/down or `k`/`j` move the cursor, which is displayed in reverse video, by a line; page
+///   up/page down and space move it by a page. The table scrolls to keep the cursor on the
+///   screen.
+/// - home/end or `g`/`G` go to the start or end of the table.
+/// - enter opens a detail panel listing every column of the line under the cursor, including
+///   those which were dropped or hidden, as keys and values; up/down scroll it, and enter, `q`
+///   or escape return to the table. When there is a selection callback (see
+///   [Pager::set_on_select]), enter selects the line instead, and `d` opens the detail panel.
+/// - left/right or `h`/`l` scroll through the columns which were dropped to fit the screen, one
+///   column at a time. Frozen columns (see [TTYGrid::set_frozen]) are always displayed.
+/// - `1` through `9` and `0` toggle the visibility of the first ten columns, re-running the
+///   layout; hidden columns are listed in brackets on the bottom line.
+/// - `e` expands rows which were clipped by [TTYGrid::set_max_row_height], or clips them again.
+/// - tab and shift-tab highlight the next and previous column header; `s` then sorts the table by
+///   the highlighted column in ascending order, in descending order when pressed again, and stops
+///   sorting when pressed a third time. The labels of the columns the table is sorted by are
+///   marked with arrows (see [TTYGrid::set_sort_indicator]).
+/// - `q`, escape or control-c leave the pager.
+///
+/// With the `mouse` feature, which captures the mouse while the pager runs, the scroll wheel
+/// moves the cursor, clicking a line moves the cursor to it, and clicking a header highlights it
+/// and sorts the table by its column like `s` does.
+pub struct Pager<'a> {
+    grid: &'a mut TTYGrid,
+    offset: usize,
+    cursor: usize,
+    cursor_colors: Option<Colors>,
+    selected: Option<usize>,
+    on_select: Option<SelectCallback<'a>>,
+    detail: Option<usize>,
+    detail_offset: usize,
+    // the header highlighted for sorting, by its position in the grid
+    column: Option<usize>,
+    quit_on_any_key: bool,
+    fits: bool,
+    // the rows of the table on each line of the screen, as last drawn
+    #[cfg(feature = "mouse")]
+    screen: Vec<Row>,
+}
+
+/// ScrollPosition is where a [Pager] was scrolled to when it was left. The next pager of the grid
+/// starts there, and it is a part of the [crate::GridState] of the grid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ScrollPosition {
+    /// The first line of the table on the screen.
+    pub offset: usize,
+    /// The line under the cursor.
+    pub cursor: usize,
+    /// How many columns were scrolled past; see [TTYGrid::set_frozen].
+    pub column_offset: usize,
+}
+
+/// TerminalGuard puts the terminal into raw mode and the alternate screen, with the cursor
+/// hidden, and restores it when it is dropped, including while unwinding from a panic, so a
+/// program which fails while drawing does not leave the terminal unusable. With the `mouse`
+/// feature, the mouse is captured as well. The [Pager] uses one while it runs; programs which
+/// redraw grids in place with [TTYGrid::write_region] can use one for the same reason. Output is
+/// written through the guard, which writes to the writer it was created with.
+///
+/// ```no_run
+/// use std::io::Write;
+/// use ttygrid::{add_line, grid, header, TerminalGuard};
+///
+/// let mut grid = grid!(header!("name")).unwrap();
+/// add_line!(grid, "web-1");
+///
+/// let mut guard = TerminalGuard::new(std::io::stdout()).unwrap();
+/// grid.write_region(&mut guard, 0, 0, 40, 10).unwrap();
+/// guard.flush().unwrap();
+/// guard.restore().unwrap();
+/// ```
+pub struct TerminalGuard<W: Write> {
+    writer: W,
+    active: bool,
+}
+
+impl<W: Write> TerminalGuard<W> {
+    /// Set up the terminal, which the writer should write to.
+    pub fn new(writer: W) -> Result<Self> {
+        terminal::enable_raw_mode()?;
+
+        // from here on, a failure restores the terminal as the guard is dropped
+        let mut guard = Self {
+            writer,
+            active: true,
+        };
+        execute!(guard.writer, EnterAlternateScreen, Hide)?;
+        #[cfg(feature = "mouse")]
+        execute!(guard.writer, event::EnableMouseCapture)?;
+
+        Ok(guard)
+    }
+
+    /// Restore the terminal now rather than when the guard is dropped, yielding any error. Later
+    /// calls, and dropping the guard, do nothing.
+    pub fn restore(&mut self) -> Result<()> {
+        if !self.active {
+            return Ok(());
+        }
+        self.active = false;
+
+        #[cfg(feature = "mouse")]
+        let mouse = execute!(self.writer, event::DisableMouseCapture);
+        #[cfg(not(feature = "mouse"))]
+        let mouse: std::io::Result<()> = Ok(());
+
+        // each step is tried even when an earlier one fails
+        let screen = execute!(self.writer, ResetColor, Show, LeaveAlternateScreen);
+        let raw = terminal::disable_raw_mode();
+
+        mouse?;
+        screen?;
+        raw?;
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for TerminalGuard<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl<W: Write> Drop for TerminalGuard<W> {
+    fn drop(&mut self) {
+        let _ = self.restore();
+    }
+}
+
+type SelectCallback<'a> = Box<dyn FnMut(usize, &GridLine) -> bool + 'a>;
+
+impl<'a> Pager<'a> {
+    pub fn new(grid: &'a mut TTYGrid) -> Self {
+        let scroll = grid.scroll.unwrap_or_default();
+        Self {
+            grid,
+            offset: scroll.offset,
+            cursor: scroll.cursor,
+            cursor_colors: None,
+            selected: None,
+            on_select: None,
+            detail: None,
+            detail_offset: 0,
+            column: None,
+            quit_on_any_key: false,
+            fits: false,
+            #[cfg(feature = "mouse")]
+            screen: Vec::new(),
+        }
+    }
+
+    /// When the whole table fits on the screen, leave the pager on any key press, instead of only
+    /// the keys listed above.
+    pub fn set_quit_on_any_key(&mut self, quit: bool) {
+        self.quit_on_any_key = quit
+    }
+
+    /// Display the line under the cursor in the colors, instead of in reverse video.
+    pub fn set_cursor_colors(&mut self, colors: Colors) {
+        self.cursor_colors = Some(colors)
+    }
+
+    /// Call the callback with the position and the contents of the line under the cursor when
+    /// enter is pressed, which selects the line. The pager is left when the callback yields true,
+    /// so picking one line of a table is:
+    ///
+    /// ```no_run
+    /// use ttygrid::{add_line, grid, header};
+    ///
+    /// let mut grid = grid!(header!("name")).unwrap();
+    /// add_line!(grid, "web-1");
+    /// add_line!(grid, "web-2");
+    ///
+    /// let mut pager = grid.pager();
+    /// pager.set_on_select(|_, _| true);
+    /// pager.run(std::io::stdout()).unwrap();
+    /// if let Some(line) = pager.selected_row() {
+    ///     println!("picked line {}", line);
+    /// }
+    /// ```
+    pub fn set_on_select(&mut self, on_select: impl FnMut(usize, &GridLine) -> bool + 'a) {
+        self.on_select = Some(Box::new(on_select))
+    }
+
+    /// The position of the line which was last selected; see [Pager::set_on_select].
+    pub fn selected_row(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Run the pager until the user leaves it, writing to the writer, which should be the
+    /// terminal (typically [std::io::stdout]).
+    pub fn run(&mut self, writer: impl Write) -> Result<()> {
+        let width = self.grid.width;
+        let column_offset = self.grid.column_offset;
+        let expand_rows = self.grid.expand_rows;
+        let sort_indicator = self.grid.sort_indicator;
+        self.grid.sort_indicator = true;
+        if let Some(scroll) = self.grid.scroll {
+            self.grid.column_offset = scroll.column_offset;
+        }
+
+        let mut guard = TerminalGuard::new(writer)?;
+        let res = self.event_loop(&mut guard);
+        guard.restore()?;
+
+        self.grid.scroll = Some(ScrollPosition {
+            offset: self.offset,
+            cursor: self.cursor,
+            column_offset: self.grid.column_offset,
+        });
+        self.grid.width = width;
+        self.grid.column_offset = column_offset;
+        self.grid.expand_rows = expand_rows;
+        self.grid.sort_indicator = sort_indicator;
+
+        res
+    }
+
+    fn event_loop(&mut self, writer: &mut impl Write) -> Result<()> {
+        loop {
+            let page = self.draw(writer)?;
+
+            match event::read()? {
+                Event::Key(key)
+                    if key.kind != KeyEventKind::Release && !self.handle_key(key, page) =>
+                {
+                    return Ok(())
+                }
+                #[cfg(feature = "mouse")]
+                Event::Mouse(mouse) => self.handle_mouse(mouse),
+                _ => {}
+            }
+        }
+    }
+
+    // yields false when the pager should exit.
+    fn handle_key(&mut self, key: KeyEvent, page: usize) -> bool {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return false;
+        }
+
+        if self.detail.is_some() {
+            match key.code {
+                KeyCode::Enter | KeyCode::Char('q') | KeyCode::Esc => self.detail = None,
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.detail_offset = self.detail_offset.saturating_sub(1)
+                }
+                KeyCode::Down | KeyCode::Char('j') => self.detail_offset += 1,
+                _ => {}
+            }
+
+            return true;
+        }
+
+        if self.quit_on_any_key && self.fits {
+            return false;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Enter if self.on_select.is_some() && self.grid.line_shown(self.cursor) => {
+                self.selected = Some(self.cursor);
+                let on_select = self.on_select.as_mut().unwrap();
+                if on_select(self.cursor, &self.grid.lines[self.cursor]) {
+                    return false;
+                }
+            }
+            KeyCode::Enter | KeyCode::Char('d') if self.grid.line_shown(self.cursor) => {
+                self.detail = Some(self.cursor);
+                self.detail_offset = 0;
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1),
+            KeyCode::PageUp => self.move_cursor(-(page as isize)),
+            KeyCode::PageDown | KeyCode::Char(' ') => self.move_cursor(page as isize),
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.grid.column_offset = self.grid.column_offset.saturating_sub(1)
+            }
+            // the columns past the offset are only dropped when some of them do not fit
+            KeyCode::Right | KeyCode::Char('l') if !self.grid.dropped_headers().is_empty() => {
+                self.grid.column_offset += 1
+            }
+            KeyCode::Char('e') => self.grid.expand_rows = !self.grid.expand_rows,
+            KeyCode::Tab => self.move_column(1),
+            KeyCode::BackTab => self.move_column(-1),
+            KeyCode::Char('s') => {
+                if let Some(idx) = self.column {
+                    self.toggle_sort(idx)
+                }
+            }
+            KeyCode::Home | KeyCode::Char('g') => self.move_cursor(isize::MIN),
+            KeyCode::End | KeyCode::Char('G') => self.move_cursor(isize::MAX),
+            KeyCode::Char(c) if c.is_ascii_digit() => {
+                // 1 is the first column, 0 is the tenth
+                let idx = (c as usize - '0' as usize + 9) % 10;
+                if idx < self.grid.headers.len() {
+                    let hidden = self.grid.hidden_index(idx);
+                    self.grid.set_hidden_index(idx, !hidden);
+                }
+            }
+            _ => {}
+        }
+
+        true
+    }
+
+    #[cfg(feature = "mouse")]
+    fn handle_mouse(&mut self, mouse: event::MouseEvent) {
+        use event::{MouseButton, MouseEventKind};
+
+        if self.detail.is_some() {
+            match mouse.kind {
+                MouseEventKind::ScrollUp => {
+                    self.detail_offset = self.detail_offset.saturating_sub(1)
+                }
+                MouseEventKind::ScrollDown => self.detail_offset += 1,
+                _ => {}
+            }
+
+            return;
+        }
+
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.move_cursor(-3),
+            MouseEventKind::ScrollDown => self.move_cursor(3),
+            MouseEventKind::Down(MouseButton::Left) => match self.screen.get(mouse.row as usize) {
+                Some(Row::Line(idx)) => self.cursor = *idx,
+                Some(Row::Header) => self.sort_at(mouse.column as usize),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    // highlights the displayed column at the position on the screen, and sorts by it.
+    #[cfg(feature = "mouse")]
+    fn sort_at(&mut self, x: usize) {
+        let mut start = self.grid.table_margin();
+        let header = self
+            .grid
+            .selected
+            .iter()
+            .zip(self.grid.selected_widths())
+            .find(|(_, width)| {
+                start += width;
+                x < start
+            })
+            .map(|(header, _)| header.clone());
+
+        if let Some(idx) = header.and_then(|h| self.grid.header_index(&h).ok()) {
+            self.column = Some(idx);
+            self.toggle_sort(idx);
+        }
+    }
+
+    // sorts by the header's column in ascending order, in descending order when the table is
+    // already sorted by it, and stops sorting when it is sorted by it in descending order.
+    fn toggle_sort(&mut self, idx: usize) {
+        use crate::SortOrder;
+
+        self.grid.sort = match self.grid.sort.first() {
+            Some((i, SortOrder::Asc)) if *i == idx => vec![(idx, SortOrder::Desc)],
+            Some((i, SortOrder::Desc)) if *i == idx => Vec::new(),
+            _ => vec![(idx, SortOrder::Asc)],
+        };
+    }
+
+    // highlights the header a number of displayed columns away from the highlighted one, wrapping
+    // around at either end. The first or last column is highlighted when none is.
+    fn move_column(&mut self, by: isize) {
+        let displayed = self
+            .grid
+            .selected
+            .iter()
+            .filter_map(|h| self.grid.header_index(h).ok())
+            .collect::<Vec<usize>>();
+        if displayed.is_empty() {
+            return;
+        }
+
+        let len = displayed.len() as isize;
+        let pos = match self
+            .column
+            .and_then(|idx| displayed.iter().position(|i| *i == idx))
+        {
+            Some(pos) => (pos as isize + by).rem_euclid(len),
+            None if by < 0 => len - 1,
+            None => 0,
+        };
+        self.column = Some(displayed[pos as usize]);
+    }
+
+    // the columns on the screen the highlighted header takes, if it is displayed.
+    fn highlighted_columns(&self) -> Option<std::ops::Range<usize>> {
+        let idx = self.column?;
+        let widths = self.grid.selected_widths();
+        let pos = self
+            .grid
+            .selected
+            .iter()
+            .position(|h| self.grid.header_index(h).ok() == Some(idx))?;
+
+        let start = self.grid.table_margin() + widths[..pos].iter().sum::<usize>();
+        Some(start..start + widths[pos])
+    }
+
+    // moves the cursor by a number of lines which are not hidden, stopping at either end. Moving
+    // by 0 moves a cursor on a hidden line to the next line which is not.
+    fn move_cursor(&mut self, by: isize) {
+        let visible = self.grid.visible_lines();
+        if visible.is_empty() {
+            return;
+        }
+
+        // the displayed lines before the cursor's, in display order: a cursor on a hidden line
+        // is on the next displayed line, which is not always the next position when sorted
+        let pos = self
+            .grid
+            .line_order()
+            .take_while(|idx| *idx != self.cursor)
+            .filter(|idx| self.grid.line_shown(*idx))
+            .count()
+            .min(visible.len() - 1);
+        let pos = (pos as isize)
+            .saturating_add(by)
+            .clamp(0, visible.len() as isize - 1);
+        self.cursor = visible[pos as usize];
+    }
+
+    // draws the screen, and yields the number of lines of the table which fit on a page.
+    fn draw(&mut self, writer: &mut impl Write) -> Result<usize> {
+        let (w, h) = terminal::size()?;
+        let (width, height) = (w as usize, h as usize);
+        // the header and delimiter, unless hidden, and the status line are always displayed
+        let header = self.grid.header_rows();
+        let page = height.saturating_sub(header + 1).max(1);
+
+        execute!(writer, ResetColor, Clear(ClearType::All))?;
+
+        if let Some(line) = self.detail {
+            self.draw_detail(writer, line, width, height)?;
+            return Ok(page);
+        }
+
+        self.grid.width = width;
+        let lines = match self
+            .grid
+            .determine_headers()
+            .and_then(|_| self.grid.styled_rows())
+        {
+            Ok(lines) => lines,
+            Err(e) => {
+                execute!(writer, MoveTo(0, 0))?;
+                write_clipped(&mut *writer, vec![(reset(), e.to_string())], width)?;
+                writer.flush()?;
+                return Ok(page);
+            }
+        };
+
+        let rows = lines.len().saturating_sub(header);
+        self.fits = rows <= page;
+        self.move_cursor(0);
+
+        // scroll so the rows of the cursor's line are on the screen
+        let cursor_rows = lines
+            .iter()
+            .skip(header)
+            .enumerate()
+            .filter(|(_, (row, _))| *row == Row::Line(self.cursor))
+            .map(|(row, _)| row)
+            .collect::<Vec<usize>>();
+        if let (Some(first), Some(last)) = (cursor_rows.first(), cursor_rows.last()) {
+            if *first < self.offset || last - first >= page {
+                self.offset = *first;
+            } else if *last >= self.offset + page {
+                self.offset = last + 1 - page;
+            }
+        }
+        self.offset = self.offset.min(rows.saturating_sub(page));
+
+        let visible = lines
+            .iter()
+            .take(header)
+            .chain(lines.iter().skip(header + self.offset).take(page));
+
+        #[cfg(feature = "mouse")]
+        self.screen.clear();
+
+        for (pos, (row, segments)) in visible.enumerate() {
+            execute!(writer, MoveTo(0, pos as u16))?;
+            #[cfg(feature = "mouse")]
+            self.screen.push(*row);
+
+            if *row == Row::Line(self.cursor) {
+                let used = segments
+                    .iter()
+                    .map(|(_, text)| text.chars().count())
+                    .sum::<usize>();
+                let mut segments = segments.clone();
+                if let Some((colors, _)) = segments.last().cloned() {
+                    segments.push((colors, " ".repeat(width.saturating_sub(used))));
+                }
+
+                if let Some(colors) = self.cursor_colors {
+                    for segment in segments.iter_mut() {
+                        segment.0 = colors;
+                    }
+                    write_clipped(&mut *writer, segments, width)?;
+                } else {
+                    execute!(writer, SetAttribute(Attribute::Reverse))?;
+                    write_clipped(&mut *writer, segments, width)?;
+                    execute!(writer, SetAttribute(Attribute::NoReverse))?;
+                }
+            } else if *row == Row::Header {
+                if self.grid.underline_header {
+                    execute!(writer, SetAttribute(Attribute::Underlined))?;
+                }
+
+                match self.highlighted_columns() {
+                    Some(columns) if columns.start < width => {
+                        let (before, rest) = split_segments(segments.clone(), columns.start);
+                        let (label, after) = split_segments(rest, columns.len());
+                        write_clipped(&mut *writer, before, width)?;
+                        execute!(writer, SetAttribute(Attribute::Reverse))?;
+                        write_clipped(&mut *writer, label, width - columns.start)?;
+                        execute!(writer, SetAttribute(Attribute::NoReverse))?;
+                        write_clipped(&mut *writer, after, width.saturating_sub(columns.end))?;
+                    }
+                    _ => write_clipped(&mut *writer, segments.clone(), width)?,
+                }
+
+                if self.grid.underline_header {
+                    execute!(writer, SetAttribute(Attribute::NoUnderline))?;
+                }
+            } else if matches!(row, Row::Line(idx) if self.grid.line_dimmed(*idx)) {
+                execute!(writer, SetAttribute(Attribute::Dim))?;
+                write_clipped(&mut *writer, segments.clone(), width)?;
+                execute!(writer, SetAttribute(Attribute::NormalIntensity))?;
+            } else {
+                write_clipped(&mut *writer, segments.clone(), width)?;
+            }
+        }
+
+        execute!(writer, ResetColor, MoveTo(0, h.saturating_sub(1)))?;
+        write_clipped(
+            &mut *writer,
+            vec![(reset(), self.status_line(rows, page))],
+            width,
+        )?;
+        writer.flush()?;
+
+        Ok(page)
+    }
+
+    // draws every column of the line as keys and values.
+    fn draw_detail(
+        &mut self,
+        writer: &mut impl Write,
+        line: usize,
+        width: usize,
+        height: usize,
+    ) -> Result<()> {
+        for item in self.grid.lines[line].items.iter_mut() {
+            item.materialize();
+        }
+
+        let pairs = self
+            .grid
+            .headers
+            .iter()
+            .map(|header| {
+                let contents = self.grid.lines[line]
+                    .items
+                    .iter()
+                    .find(|item| std::rc::Rc::ptr_eq(&item.header, header))
+                    .map(|item| self.grid.redacted(header, &item.contents).into_owned())
+                    .unwrap_or_default();
+                (self.grid.label(&header.borrow()), contents)
+            })
+            .collect::<Vec<(String, String)>>();
+
+        // long values are wrapped into the width the keys leave, instead of being dropped
+        let key_len = pairs
+            .iter()
+            .map(|(key, _)| key.chars().count())
+            .max()
+            .unwrap_or_default();
+
+        let mut detail = TTYGrid::key_value(pairs)?;
+        detail.width = width;
+        for header in detail.headers.iter().skip(1) {
+            let gaps = detail
+                .headers
+                .iter()
+                .map(|h| h.borrow().gap() + 1)
+                .sum::<usize>();
+            header.borrow_mut().max_width = Some(width.saturating_sub(key_len + gaps).max(1));
+        }
+        detail.set_theme(self.grid.theme);
+        detail.set_charset(self.grid.charset);
+        detail.set_wrap(true);
+
+        let page = height.saturating_sub(1).max(1);
+        let lines = match detail
+            .determine_headers()
+            .and_then(|_| detail.styled_lines())
+        {
+            Ok(lines) => lines,
+            Err(e) => vec![vec![(reset(), e.to_string())]],
+        };
+        self.detail_offset = self.detail_offset.min(lines.len().saturating_sub(page));
+
+        for (row, segments) in lines
+            .into_iter()
+            .skip(self.detail_offset)
+            .take(page)
+            .enumerate()
+        {
+            execute!(writer, MoveTo(0, row as u16))?;
+            write_clipped(&mut *writer, segments, width)?;
+        }
+
+        execute!(
+            writer,
+            ResetColor,
+            MoveTo(0, height.saturating_sub(1) as u16)
+        )?;
+        write_clipped(
+            &mut *writer,
+            vec![(
+                reset(),
+                format!("line {}/{} enter:back", line + 1, self.grid.lines.len()),
+            )],
+            width,
+        )?;
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    fn status_line(&self, rows: usize, page: usize) -> String {
+        let columns = self
+            .grid
+            .headers
+            .0
+            .iter()
+            .enumerate()
+            .take(10)
+            .map(|(idx, header)| {
+                let text = self.grid.label(&header.borrow());
+                if self.grid.hidden_index(idx) {
+                    format!("{}:[{}]", (idx + 1) % 10, text)
+                } else {
+                    format!("{}:{}", (idx + 1) % 10, text)
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        let (left, right) = self.grid.charset.arrows();
+        let mut scroll = String::new();
+        if self.grid.column_offset > 0 || !self.grid.dropped_headers().is_empty() {
+            scroll.push(' ');
+        }
+        if self.grid.column_offset > 0 {
+            scroll.push_str(left);
+        }
+        if !self.grid.dropped_headers().is_empty() {
+            scroll.push_str(right);
+        }
+
+        format!(
+            "{}-{}/{}{} {} q:quit",
+            (self.offset + 1).min(rows),
+            (self.offset + page).min(rows),
+            rows,
+            scroll,
+            columns
+        )
+    }
+}
+
+impl TTYGrid {
+    /// Redraw the grid on the alternate screen of the terminal every interval, in the manner of
+    /// `watch(1)`, until control-c is pressed. The refresh function is called before each redraw
+    /// to update the lines of the grid, such as by clearing them and adding the latest ones; an
+    /// error from it stops watching and is yielded. The grid is laid out for the terminal's size
+    /// on every redraw, and redrawn at once when the terminal is resized. Only the rows which
+    /// changed since the last redraw are written, so the screen does not flicker; it is only
+    /// cleared when watching starts and when the terminal is resized. The terminal is restored
+    /// when watching stops (see [TerminalGuard]).
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use ttygrid::{add_line, grid, header};
+    ///
+    /// let mut grid = grid!(header!("time")).unwrap();
+    /// grid.watch(Duration::from_secs(2), |grid| {
+    ///     grid.clear_lines();
+    ///     add_line!(grid, format!("{:?}", std::time::SystemTime::now()));
+    ///     Ok(())
+    /// })
+    /// .unwrap();
+    /// ```
+    pub fn watch(
+        &mut self,
+        interval: Duration,
+        mut refresh: impl FnMut(&mut TTYGrid) -> Result<()>,
+    ) -> Result<()> {
+        let mut guard = TerminalGuard::new(std::io::stdout())?;
+        let mut drawn = Vec::new();
+        let mut size = None;
+
+        let res = (|| loop {
+            refresh(self)?;
+            let next = Instant::now() + interval;
+            let mut redraw = true;
+
+            loop {
+                if redraw {
+                    let (cols, rows) = terminal::size()?;
+                    if size != Some((cols, rows)) {
+                        execute!(guard, Clear(ClearType::All))?;
+                        drawn.clear();
+                        size = Some((cols, rows));
+                    }
+
+                    self.redraw_changes(&mut guard, &mut drawn, cols, rows)?;
+                    guard.flush()?;
+                    redraw = false;
+                }
+
+                match event::poll(next.saturating_duration_since(Instant::now()))? {
+                    false => break,
+                    true => match event::read()? {
+                        Event::Key(key)
+                            if key.code == KeyCode::Char('c')
+                                && key.modifiers.contains(KeyModifiers::CONTROL) =>
+                        {
+                            return Ok(())
+                        }
+                        Event::Resize(..) => redraw = true,
+                        _ => {}
+                    },
+                }
+            }
+        })();
+
+        guard.restore()?;
+        res
+    }
+
+    // writes the rows of the grid, laid out for the rectangle at the top left of the terminal,
+    // which differ from the rows drawn before, and clears what is left of them and of the rows
+    // past the end of the table. The drawn rows are replaced with the new ones.
+    fn redraw_changes(
+        &mut self,
+        mut writer: impl Write,
+        drawn: &mut Vec<Segments>,
+        width: u16,
+        height: u16,
+    ) -> Result<()> {
+        let start = Instant::now();
+        let terminal_width = std::mem::replace(&mut self.width, width as usize);
+        let lines = self.determine_headers().and_then(|_| self.styled_lines());
+        self.width = terminal_width;
+
+        let lines = lines?
+            .into_iter()
+            .take(height as usize)
+            .collect::<Vec<Segments>>();
+
+        for (row, line) in lines.iter().enumerate() {
+            if drawn.get(row) == Some(line) {
+                continue;
+            }
+
+            execute!(writer, MoveTo(0, row as u16))?;
+            write_clipped(&mut writer, line.clone(), width as usize)?;
+            execute!(writer, ResetColor, Clear(ClearType::UntilNewLine))?;
+        }
+
+        for row in lines.len()..drawn.len() {
+            execute!(writer, MoveTo(0, row as u16), Clear(ClearType::CurrentLine))?;
+        }
+
+        *drawn = lines;
+        self.notify_render_complete(start);
+        Ok(())
+    }
+}
+
+// splits the colored segments of a line at the column, padding them with spaces to it.
+fn split_segments(segments: Vec<(Colors, String)>, at: usize) -> (Segments, Segments) {
+    let (mut before, mut after) = (Vec::new(), Vec::new());
+    let mut used = 0;
+
+    for (colors, text) in segments {
+        let len = text.chars().count();
+        if used >= at {
+            after.push((colors, text));
+        } else if used + len <= at {
+            before.push((colors, text));
+        } else {
+            before.push((colors, text.chars().take(at - used).collect()));
+            after.push((colors, text.chars().skip(at - used).collect()));
+        }
+        used += len;
+    }
+
+    if used < at {
+        before.push((reset(), " ".repeat(at - used)));
+    }
+
+    (before, after)
+}
+
+fn reset() -> Colors {
+    Colors::new(Color::Reset, Color::Reset)
+}
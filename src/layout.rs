@@ -0,0 +1,138 @@
+/// Column describes a column to the layout [Engine]: the width it needs, including any padding,
+/// and its priority. Higher priorities are kept in favor of lower ones when the columns do not
+/// all fit; see [crate::Priority].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Column {
+    pub width: usize,
+    pub priority: usize,
+}
+
+impl Column {
+    pub fn new(width: usize, priority: usize) -> Self {
+        Self { width, priority }
+    }
+}
+
+/// Layout is the result of [Engine::layout]: the indexes of the columns to display, in the order
+/// they were given, and their widths.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Layout {
+    pub columns: Vec<usize>,
+    pub widths: Vec<usize>,
+}
+
+impl Layout {
+    /// The total width of the selected columns.
+    pub fn width(&self) -> usize {
+        self.widths.iter().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+}
+
+/// Engine decides which columns fit in a terminal of a given width. It is the algorithm
+/// [crate::TTYGrid] uses to lay out its headers, and works on plain column widths and priorities
+/// so it can be reused by other tools.
+///
+/// Every prefix of the columns is considered; columns are dropped from each prefix, lowest
+/// priority first, until it fits. The candidate with the highest total priority wins.
+///
+/// ```
+/// use ttygrid::layout::{Column, Engine};
+///
+/// let columns = [
+///     Column::new(10, 100_000),
+///     Column::new(30, 1_000),
+///     Column::new(20, 10_000),
+/// ];
+///
+/// let layout = Engine::new(40).layout(&columns);
+/// assert_eq!(layout.columns, vec![0, 2]);
+/// assert_eq!(layout.widths, vec![10, 20]);
+/// assert_eq!(layout.width(), 30);
+///
+/// assert_eq!(Engine::new(80).layout(&columns).columns, vec![0, 1, 2]);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Engine {
+    width: usize,
+}
+
+impl Engine {
+    /// Create an engine for a terminal of the width.
+    pub fn new(width: usize) -> Self {
+        Self { width }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Select the columns which fit in the width. The layout is empty if no column fits.
+    pub fn layout(&self, columns: &[Column]) -> Layout {
+        let all = (0..columns.len()).collect::<Vec<usize>>();
+
+        if total(columns, &all) <= self.width {
+            return self.finish(columns, all);
+        }
+
+        let mut candidates: Vec<(Rank, Vec<usize>)> = Vec::new();
+        let mut len = columns.len();
+
+        while len > 0 {
+            let mut selected = all.iter().take(len).copied().collect::<Vec<usize>>();
+            let mut width = total(columns, &selected);
+
+            while width > self.width {
+                let mut to_remove = None;
+                let mut lowest_priority = usize::MAX;
+
+                for (idx, column) in selected.iter().enumerate() {
+                    let priority = columns[*column].priority;
+                    if priority < lowest_priority {
+                        to_remove = Some(idx);
+                        lowest_priority = priority;
+                    }
+                }
+
+                if let Some(to_remove) = to_remove {
+                    selected.remove(to_remove);
+                    width = total(columns, &selected);
+                } else {
+                    width = 0 // bury it
+                }
+            }
+
+            let priorities = selected
+                .iter()
+                .map(|idx| columns[*idx].priority)
+                .collect::<Vec<usize>>();
+
+            candidates.push(((priorities.iter().sum(), priorities, width), selected));
+            len -= 1;
+        }
+
+        candidates.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        match candidates.pop() {
+            Some((_, selected)) => self.finish(columns, selected),
+            None => Layout::default(),
+        }
+    }
+
+    fn finish(&self, columns: &[Column], selected: Vec<usize>) -> Layout {
+        Layout {
+            widths: selected.iter().map(|idx| columns[*idx].width).collect(),
+            columns: selected,
+        }
+    }
+}
+
+// candidates are ranked by their total priority, then their priorities in order, then their width.
+type Rank = (usize, Vec<usize>, usize);
+
+fn total(columns: &[Column], selected: &[usize]) -> usize {
+    selected.iter().map(|idx| columns[*idx].width).sum()
+}
@@ -19,16 +19,125 @@
 //! most comprehensive documentation.
 use anyhow::{anyhow, Result};
 use crossterm::{
-    execute,
-    style::{Color, Colors, Print, SetColors},
+    cursor::MoveTo,
+    execute, queue,
+    style::{Attribute, Color, Colors, Print, ResetColor, SetAttribute, SetColors},
 };
-use std::{cell::RefCell, fmt, rc::Rc, usize::MAX};
+use std::{borrow::Cow, cell::RefCell, fmt, iter::FromIterator, rc::Rc};
 
+mod age;
+mod charset;
+mod diff;
+mod explain;
+mod export;
+mod filter;
+mod icon;
+mod interactive;
+pub mod layout;
 mod macros;
-pub use macros::*;
+mod marker;
+mod memory;
+mod observer;
+mod plain;
+mod profile;
+#[cfg(feature = "arrow")]
+mod record_batch;
+mod redact;
+mod sort;
+#[cfg(feature = "rusqlite")]
+mod sqlite;
+#[cfg(feature = "serde")]
+mod state;
+mod style;
+mod template;
+mod term;
+pub mod test;
+mod theme;
+mod uninformative;
+mod validate;
+mod vocabulary;
+pub use age::AgeCell;
+pub use charset::Charset;
+pub use diff::{Change, GridDiff};
+pub use export::{ExportColumns, ExportFormat, JsonLinesWriter, QuoteStyle};
+pub use filter::FilterDisplay;
+pub use icon::IconColumn;
+pub use marker::MarkerColumn;
+pub use observer::GridObserver;
+
+pub use interactive::{Pager, ScrollPosition, TerminalGuard};
+use layout::{Column, Engine};
+pub use profile::{ColorDepth, TerminalProfile, TerminalProfiles};
+pub use redact::RedactionPolicy;
+use sort::Comparator;
+pub use sort::SortOrder;
+#[cfg(feature = "serde")]
+pub use state::GridState;
+pub use style::{Style, StyledSpan};
+pub use template::GridTemplate;
+pub use term::{Terminal, WidthSource};
+pub use theme::{ColorScale, Theme};
+#[cfg(feature = "derive")]
+pub use ttygrid_derive::TtyGridRow;
+pub use uninformative::UninformativeColumns;
+pub use validate::ValidationError;
+pub use vocabulary::Vocabulary;
+
+// used by the code generated by the derive feature.
+#[doc(hidden)]
+pub mod __private {
+    pub use anyhow::Result;
+}
 
 pub type SafeGridHeader = Rc<RefCell<GridHeader>>;
 
+// wraps a callback so headers holding one can still be compared and printed. Hooks are only equal
+// when they are the same callback.
+struct Hook<F: ?Sized>(Rc<F>);
+
+impl<F: ?Sized> Clone for Hook<F> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<F: ?Sized> PartialEq for Hook<F> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::as_ptr(&self.0) as *const () == Rc::as_ptr(&other.0) as *const ()
+    }
+}
+
+impl<F: ?Sized> Eq for Hook<F> {}
+
+impl<F: ?Sized> fmt::Debug for Hook<F> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "Hook({:p})", Rc::as_ptr(&self.0) as *const ())
+    }
+}
+
+type Transform = Hook<dyn Fn(&str) -> String>;
+type Validator = Hook<dyn Fn(&str) -> std::result::Result<(), String>>;
+type Abbreviator = Hook<dyn Fn(&str, usize) -> String>;
+type LazyContents = Hook<dyn Fn(&GridHeader) -> String>;
+type DegradedCallback = Rc<dyn Fn(&[HeaderId])>;
+type LabelProvider = Rc<dyn Fn(&str) -> Option<String>>;
+// a line of output, as colored segments.
+type Segments = Vec<(Colors, String)>;
+// the width and padding a header had before it became an icon column
+type SavedLayout = (Option<Width>, Option<usize>);
+
+// what a row of styled output displays.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Row {
+    Header,
+    Delimiter,
+    // the grid line at the position
+    Line(usize),
+    // anything else, such as the legend
+    Other,
+}
+type RowRenderer = Rc<dyn Fn(&GridLine, &[usize], &mut dyn std::io::Write) -> Result<()>>;
+
 /// HeaderList defines a list of headers. This is typically composed as a part of the process from
 /// [crate::header!] and [crate::grid!] and is not constructed directly.
 #[derive(Default, Clone, Eq, PartialEq, PartialOrd, Ord)]
@@ -50,6 +159,46 @@ impl HeaderList {
     pub fn get(&self, idx: usize) -> Option<&SafeGridHeader> {
         self.0.get(idx)
     }
+
+    pub fn push(&mut self, header: SafeGridHeader) {
+        self.0.push(header)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, SafeGridHeader> {
+        self.0.iter()
+    }
+}
+
+impl std::ops::Index<usize> for HeaderList {
+    type Output = SafeGridHeader;
+
+    fn index(&self, idx: usize) -> &Self::Output {
+        &self.0[idx]
+    }
+}
+
+impl IntoIterator for HeaderList {
+    type Item = SafeGridHeader;
+    type IntoIter = std::vec::IntoIter<SafeGridHeader>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a HeaderList {
+    type Item = &'a SafeGridHeader;
+    type IntoIter = std::slice::Iter<'a, SafeGridHeader>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl FromIterator<SafeGridHeader> for HeaderList {
+    fn from_iter<I: IntoIterator<Item = SafeGridHeader>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
 }
 
 impl fmt::Display for HeaderList {
@@ -62,7 +211,7 @@ impl fmt::Display for HeaderList {
                 width = header
                     .borrow()
                     .max_len
-                    .unwrap_or(header.borrow().text.len() + 2)
+                    .unwrap_or(header.borrow().text.chars().count() + 2)
             )?
         }
         Ok(())
@@ -73,40 +222,259 @@ impl fmt::Display for HeaderList {
 /// This is typically constructed by [crate::header!] and is not constructed directly.
 ///
 /// Several methods can adjust the content of the header after the fact, and should be reviewed.
+///
+/// With the `serde` feature enabled, headers may be serialized and deserialized, which allows
+/// column definitions to be kept in a configuration file. Only the definition of the header is
+/// stored; layout state computed while rendering is not.
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct GridHeader {
+    #[cfg_attr(feature = "serde", serde(skip))]
     index: Option<usize>,
-    text: &'static str,
+    key: Option<Cow<'static, str>>,
+    text: Cow<'static, str>,
     min_size: Option<usize>,
     max_pad: Option<usize>,
     priority: usize,
+    #[cfg_attr(feature = "serde", serde(skip))]
     max_len: Option<usize>,
+    alignment: Alignment,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    transform: Option<Transform>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    validator: Option<Validator>,
+    max_width: Option<usize>,
+    default_hidden: bool,
+    width: Option<Width>,
+    abbreviations: Vec<(String, String)>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    abbreviator: Option<Abbreviator>,
+    pin: Option<Pin>,
+    pad_char: char,
+}
+
+/// Priority defines named tiers which may be used in place of a bare priority number; see
+/// [GridHeader::set_tier]. Each tier converts to a [usize] priority far above the one below it, so
+/// tiers always win over small hand-picked numbers.
+///
+/// [Priority::Essential] columns are additionally guaranteed to be displayed: if they cannot all
+/// fit in the terminal, rendering yields an error instead of dropping them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    Debug = 0,
+    Optional = 1_000,
+    Important = 10_000,
+    Essential = 100_000,
+}
+
+impl From<Priority> for usize {
+    fn from(priority: Priority) -> Self {
+        priority as usize
+    }
+}
+
+/// Alignment controls how content is positioned within its column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Alignment {
+    #[default]
+    Left,
+    Right,
+    Center,
+    /// Align numbers on their decimal point, padding both sides; numbers without one are aligned
+    /// as if it followed their last digit. Headers are aligned to the right.
+    Decimal,
+    /// Align values made of a number and a unit, such as `12 MiB` or `3 GiB`, on the number:
+    /// numbers are aligned to the right, followed by their units aligned to the left in a slot as
+    /// wide as the longest unit. Values without a unit or a number are aligned as if they were all
+    /// number. Headers are aligned to the right.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header};
+    ///
+    /// let mut grid = grid!(header!("name"), header!("size", align = unit)).unwrap();
+    /// add_line!(grid, "a", "12 MiB");
+    /// add_line!(grid, "b", "3 GiB");
+    /// add_line!(grid, "c", "512 B");
+    ///
+    /// let output = grid.display().unwrap();
+    /// let sizes = output
+    ///     .lines()
+    ///     .skip(2)
+    ///     .map(|l| l[l.find(' ').unwrap()..].trim_start())
+    ///     .collect::<Vec<&str>>();
+    /// assert_eq!(sizes, vec!["12 MiB", "3 GiB", "512 B"]);
+    ///
+    /// let columns = output
+    ///     .lines()
+    ///     .skip(2)
+    ///     .map(|l| l.rfind(|c: char| c.is_ascii_digit()))
+    ///     .collect::<Vec<_>>();
+    /// assert!(columns.windows(2).all(|w| w[0] == w[1]));
+    /// ```
+    Unit,
+}
+
+/// Pin keeps a column at an edge of the table, regardless of the order of the headers; see
+/// [GridHeader::set_pin].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Pin {
+    Left,
+    Right,
+}
+
+/// Width sizes a column independently of its items; see [GridHeader::set_width]. Items which do
+/// not fit are clipped with an ellipsis, or wrapped when the grid wraps (see [TTYGrid::set_wrap]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Width {
+    /// A fixed number of characters for the items, not counting the space between the column and
+    /// the next one.
+    Chars(usize),
+    /// A percentage of the width of the terminal, including the space between the column and the
+    /// next one.
+    Percent(usize),
+    /// A share of the width left over once the columns have been laid out, in proportion to the
+    /// weights of the other weighted columns. The column is at least as wide as its items, as if
+    /// it had no width set.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, Width};
+    ///
+    /// let mut grid = grid!(header!("name", width = Width::Weight(1)), header!("size")).unwrap();
+    /// add_line!(grid, "web-1", 10);
+    ///
+    /// let output = grid.display().unwrap();
+    /// let lines = output.lines().collect::<Vec<&str>>();
+    /// assert_eq!(lines[0].find("size"), lines[2].find("10"));
+    /// assert!(lines[2].find("10") > Some(60));
+    /// ```
+    Weight(usize),
+}
+
+/// Create a grid of columns which are only known at runtime, such as when pivoting data, from
+/// their texts and priorities, in order. Lines can be added with [TTYGrid::add_contents].
+///
+/// ```
+/// let months = ["jan", "feb", "mar"];
+/// let mut columns = vec![("region".to_string(), 10)];
+/// columns.extend(months.iter().map(|m| (m.to_string(), 1)));
+///
+/// let mut grid = ttygrid::grid_from(columns).unwrap();
+/// grid.add_contents(vec!["east".into(), "1".into(), "2".into(), "3".into()])
+///     .unwrap();
+/// assert_eq!(grid.column_names(), vec!["region", "jan", "feb", "mar"]);
+/// ```
+pub fn grid_from(columns: Vec<(String, usize)>) -> Result<TTYGrid> {
+    TTYGrid::new(
+        columns
+            .into_iter()
+            .map(|(text, priority)| {
+                Rc::new(RefCell::new(
+                    GridHeader::default().set_text(text).set_priority(priority),
+                ))
+            })
+            .collect(),
+    )
+}
+
+// the number of characters from the decimal point to the end of the text, if it has one.
+fn fraction_len(text: &str) -> usize {
+    text.rfind('.')
+        .map(|idx| text[idx..].chars().count())
+        .unwrap_or_default()
+}
+
+// the number of characters following the number the text starts with, if it starts with one.
+fn unit_len(text: &str) -> usize {
+    let number = text
+        .char_indices()
+        .find(|(idx, c)| !(c.is_ascii_digit() || ".,".contains(*c) || (*idx == 0 && *c == '-')))
+        .map(|(idx, _)| idx)
+        .unwrap_or(text.len());
+
+    if text[..number].contains(|c: char| c.is_ascii_digit()) {
+        text[number..].chars().count()
+    } else {
+        0
+    }
+}
+
+// the number of characters of the text which follow the point the alignment lines up on.
+fn suffix_len(text: &str, alignment: Alignment) -> usize {
+    match alignment {
+        Alignment::Decimal => fraction_len(text),
+        Alignment::Unit => unit_len(text),
+        _ => 0,
+    }
+}
+
+// pads the text to width, aligning it within the part of the width which is not the gap between
+// columns.
+fn align(text: &str, width: usize, gap: usize, alignment: Alignment) -> String {
+    let content = width.saturating_sub(gap);
+    let aligned = match alignment {
+        Alignment::Left => text.to_string(),
+        Alignment::Right | Alignment::Decimal | Alignment::Unit => format!("{:>content$}", text),
+        Alignment::Center => format!("{:^content$}", text),
+    };
+
+    format!("{:<width$}", aligned)
+}
+
+// fills the padding of aligned text with the leader character, leaving a space next to the text
+// and at either end.
+fn lead(aligned: &str, alignment: Alignment, pad: char) -> String {
+    let leader = |n: usize| match n {
+        0..=2 => " ".repeat(n),
+        n => format!(" {} ", pad.to_string().repeat(n - 2)),
+    };
+
+    match alignment {
+        Alignment::Left => {
+            let text = aligned.trim_end_matches(' ');
+            format!("{}{}", text, leader(aligned.len() - text.len()))
+        }
+        Alignment::Right | Alignment::Decimal | Alignment::Unit => {
+            let text = aligned.trim_start_matches(' ');
+            format!("{}{}", leader(aligned.len() - text.len()), text)
+        }
+        Alignment::Center => aligned.to_string(),
+    }
 }
 
 impl Default for GridHeader {
     fn default() -> Self {
         Self {
             index: None,
-            text: "",
+            key: None,
+            text: Cow::Borrowed(""),
             min_size: None,
             max_pad: Some(4),
             priority: 0,
             max_len: None,
+            alignment: Alignment::Left,
+            transform: None,
+            validator: None,
+            max_width: None,
+            default_hidden: false,
+            width: None,
+            abbreviations: Vec::new(),
+            abbreviator: None,
+            pin: None,
+            pad_char: ' ',
         }
     }
 }
 
 impl PartialOrd for GridHeader {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        if self.index.is_some() {
-            Some(
-                self.priority
-                    .cmp(&other.priority)
-                    .then(self.index.cmp(&other.index)),
-            )
-        } else {
-            Some(self.priority.cmp(&other.priority))
-        }
+        Some(self.cmp(other))
     }
 }
 
@@ -128,9 +496,22 @@ impl GridHeader {
         self.max_len = Some(len)
     }
 
-    /// Set the text of this header.
-    pub fn set_text(mut self, text: &'static str) -> Self {
-        self.text = text;
+    /// The maximum length of items belonging to this header, as calculated by the last layout.
+    pub fn max_len(&self) -> Option<usize> {
+        self.max_len
+    }
+
+    /// Set the text of this header, which may be a literal or, for columns only known at runtime,
+    /// a [String].
+    pub fn set_text(mut self, text: impl Into<Cow<'static, str>>) -> Self {
+        self.text = text.into();
+        self
+    }
+
+    /// Set the key of this header, which identifies it independently of its display text. If the
+    /// key is not set, the text is used. See [TTYGrid::set_label_provider].
+    pub fn set_key(mut self, key: impl Into<Cow<'static, str>>) -> Self {
+        self.key = Some(key.into());
         self
     }
 
@@ -141,297 +522,4793 @@ impl GridHeader {
         self
     }
 
-    /// Set the position this header lives within the column list. 0 is the first position.
-    pub fn set_index(&mut self, idx: usize) {
-        self.index = Some(idx);
+    /// Set the priority of this header to one of the named [Priority] tiers.
+    pub fn set_tier(self, tier: Priority) -> Self {
+        self.set_priority(tier.into())
     }
 
-    pub fn text(&self) -> &str {
-        self.text.clone()
+    /// Set the alignment of the header and its items within the column.
+    pub fn set_alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
     }
 
-    pub fn priority(&self) -> usize {
-        self.priority
+    /// Size this column by the width instead of by its items; see [Width].
+    pub fn set_width(mut self, width: Width) -> Self {
+        self.width = Some(width);
+        self
     }
-}
 
-/// GridItem is the encapsulation of a piece of content. It is usually created by invoking
-/// [crate::add_line!] and is not instantiated directly.
-#[derive(Clone, Debug, Default)]
-pub struct GridItem {
-    header: SafeGridHeader,
-    contents: String,
-    max_len: Option<usize>,
-}
+    pub fn width(&self) -> Option<Width> {
+        self.width
+    }
 
-impl GridItem {
-    pub fn new(header: SafeGridHeader, contents: String) -> Self {
-        Self {
-            header,
-            contents,
-            max_len: None,
+    // the width available to the contents of items of a column whose width is set, once it has
+    // been laid out.
+    fn content_width(&self) -> Option<usize> {
+        match (self.width, self.max_len) {
+            (Some(_), Some(max_len)) => Some(max_len.saturating_sub(self.gap())),
+            _ => None,
         }
     }
 
-    fn len(&self) -> usize {
-        self.contents.len() + 1 // right padding
+    /// Hide this column unless it is asked for, by showing it with [TTYGrid::set_hidden] or
+    /// including it in the active view (see [TTYGrid::define_view]). This is meant for verbose or
+    /// debugging columns, which should not appear just because the terminal is wide.
+    pub fn set_default_hidden(mut self, hidden: bool) -> Self {
+        self.default_hidden = hidden;
+        self
     }
 
-    fn set_max_len(&mut self, max_len: usize) {
-        self.max_len = Some(max_len)
+    pub fn is_default_hidden(&self) -> bool {
+        self.default_hidden
     }
-}
 
-impl fmt::Display for GridItem {
-    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            formatter,
-            "{:<max_len$}",
-            self.contents,
-            max_len = self.max_len.unwrap_or(self.len())
-        )
+    /// Display this column at the left or right edge of the table, such as a status flag on the
+    /// far right, regardless of where the header is declared. Pinned columns are laid out and
+    /// dropped like any other; only their position changes. Columns pinned to the same edge keep
+    /// the order of their headers.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, Pin};
+    ///
+    /// let mut grid = grid!(
+    ///     header!("status", pin = Pin::Right),
+    ///     header!("name"),
+    ///     header!("id", pin = Pin::Left)
+    /// )
+    /// .unwrap();
+    /// add_line!(grid, "ok", "web-1", 1);
+    ///
+    /// let output = grid.display().unwrap();
+    /// let words = output.lines().next().unwrap().split_whitespace().collect::<Vec<&str>>();
+    /// assert_eq!(words, vec!["id", "name", "status"]);
+    /// ```
+    pub fn set_pin(mut self, pin: Pin) -> Self {
+        self.pin = Some(pin);
+        self
     }
-}
 
-/// Usually constructed by [crate::grid!], this is the outer object of the whole library, all
-/// things are held by it in some form. Please review the impl for methods which can be used to
-/// adjust the properties of the grid once created.
-#[derive(Clone)]
-pub struct TTYGrid {
-    headers: HeaderList,
-    selected: HeaderList,
-    lines: Vec<GridLine>,
-    width: usize,
-    header_color: Colors,
-    delimiter_color: Colors,
-    primary_color: Colors,
-    secondary_color: Colors,
-}
+    pub fn pin(&self) -> Option<Pin> {
+        self.pin
+    }
 
-impl TTYGrid {
-    pub fn new(headers: Vec<SafeGridHeader>) -> Result<Self> {
-        let (w, _) = crossterm::terminal::size()?;
-        let width = w as usize;
+    /// Make this column at least the width, in characters, regardless of its items. This is also
+    /// the width lazy items are assumed to have before they are produced; see
+    /// [TTYGrid::add_lazy_line].
+    pub fn set_min_size(mut self, size: usize) -> Self {
+        self.min_size = Some(size);
+        self
+    }
 
-        Ok(Self {
-            selected: HeaderList::new(),
-            headers: HeaderList(headers),
-            lines: Vec::new(),
-            width,
-            header_color: Colors::new(Color::Reset, Color::Reset),
-            delimiter_color: Colors::new(Color::Reset, Color::Reset),
-            primary_color: Colors::new(Color::Reset, Color::Reset),
-            secondary_color: Colors::new(Color::Reset, Color::Reset),
-        })
+    /// Limit the items of this column to a width, in characters, when the grid wraps; see
+    /// [TTYGrid::set_wrap]. Longer items are wrapped onto several lines, breaking words according
+    /// to [TTYGrid::set_word_break].
+    pub fn set_max_width(mut self, width: usize) -> Self {
+        self.max_width = Some(width);
+        self
     }
 
-    /// Sets the delimiter color; the dashes between the header and the content.
-    pub fn set_delimiter_color(&mut self, colors: Colors) {
-        self.delimiter_color = colors
+    /// Shorten items which do not fit a column whose width is set (see [GridHeader::set_width]) by
+    /// replacing words with their abbreviations, such as "production" with "prod", before
+    /// clipping them with an ellipsis. The abbreviations are tried in order, until the item
+    /// fits. Items which fit are displayed in full.
+    ///
+    /// ```
+    /// use std::{cell::RefCell, rc::Rc};
+    /// use ttygrid::{add_line, grid, GridHeader, Width};
+    ///
+    /// let env = GridHeader::default()
+    ///     .set_text("env")
+    ///     .set_width(Width::Chars(12))
+    ///     .set_abbreviations(&[("production", "prod"), ("development", "dev")]);
+    /// let mut grid = grid!(Rc::new(RefCell::new(env))).unwrap();
+    /// add_line!(grid, "staging");
+    /// add_line!(grid, "production-eu");
+    ///
+    /// let output = grid.display().unwrap();
+    /// assert_eq!(output.lines().skip(2).collect::<Vec<&str>>(), vec!["staging", "prod-eu"]);
+    /// ```
+    pub fn set_abbreviations(mut self, abbreviations: &[(&str, &str)]) -> Self {
+        self.abbreviations = abbreviations
+            .iter()
+            .map(|(long, short)| (long.to_string(), short.to_string()))
+            .collect();
+        self
     }
 
-    /// Sets the header color.
-    pub fn set_header_color(&mut self, colors: Colors) {
-        self.header_color = colors
+    /// Set a function which shortens items which still do not fit a column whose width is set
+    /// once the abbreviations (see [GridHeader::set_abbreviations]) are applied. It is given the
+    /// item and the width available; whatever it yields is clipped if it is still too long.
+    pub fn set_abbreviator(
+        mut self,
+        abbreviator: impl Fn(&str, usize) -> String + 'static,
+    ) -> Self {
+        self.abbreviator = Some(Hook(Rc::new(abbreviator)));
+        self
     }
 
-    /// Sets the primary color; colors will alternate between primary and secondary per row as the
-    /// table is built.
-    pub fn set_primary_color(&mut self, colors: Colors) {
-        self.primary_color = colors
+    // shortens the text with the abbreviations and the abbreviator until it fits the width.
+    fn abbreviate(&self, text: &str, width: usize) -> String {
+        let fits = |text: &str| text.chars().count() <= width;
+
+        let mut ret = text.to_string();
+        for (long, short) in self.abbreviations.iter() {
+            if fits(&ret) {
+                return ret;
+            }
+            ret = ret.replace(long.as_str(), short);
+        }
+
+        match &self.abbreviator {
+            Some(abbreviator) if !fits(&ret) => (abbreviator.0)(&ret, width),
+            _ => ret,
+        }
     }
 
-    /// Sets the secondary color; colors will alternate between primary and secondary per row as
-    /// the table is built.
-    pub fn set_secondary_color(&mut self, colors: Colors) {
-        self.secondary_color = colors
+    /// Set the padding between this column and the next, in addition to the three spaces which
+    /// always separate columns. The default is 4.
+    pub fn set_max_pad(mut self, pad: usize) -> Self {
+        self.max_pad = Some(pad);
+        self
     }
 
-    pub fn add_line(&mut self, item: GridLine) {
-        self.lines.push(item)
+    /// Fill the space between the items of this column and the next column with the character,
+    /// such as `.` or `·` for the dot leaders of key-value or table of contents style tables. One
+    /// space is kept on either side of the leader. Left aligned items are followed by the leader,
+    /// and right aligned ones preceded by it; centered and empty items are padded with spaces, as
+    /// is the header. The default is a space.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header};
+    ///
+    /// let mut grid = grid!(header!("chapter", pad = 0, pad_char = '.'), header!("page")).unwrap();
+    /// add_line!(grid, "Introduction", 1);
+    /// add_line!(grid, "Layout", 12);
+    ///
+    /// let output = grid.display().unwrap();
+    /// assert_eq!(output.lines().nth(3), Some("Layout ....... 12"));
+    /// ```
+    pub fn set_pad_char(mut self, pad: char) -> Self {
+        self.pad_char = pad;
+        self
     }
 
-    pub fn clear_lines(&mut self) {
-        self.lines.clear()
+    pub fn pad_char(&self) -> char {
+        self.pad_char
     }
 
-    pub fn headers(&self) -> HeaderList {
-        self.headers.clone()
+    pub fn alignment(&self) -> Alignment {
+        self.alignment
     }
 
-    pub fn select(&mut self, header: SafeGridHeader, idx: usize) {
-        // update index (still an issue)
-        header.borrow_mut().set_index(idx);
-        self.selected.0.push(header)
+    // the space between the widest item and the next column.
+    fn gap(&self) -> usize {
+        self.max_pad.unwrap_or(0) + 3
     }
 
-    pub fn is_selected(&self, header: SafeGridHeader) -> bool {
-        self.selected.0.contains(&header)
+    /// Set a function which rewrites the contents of items belonging to this header before they
+    /// are measured and displayed, such as to trim them, or shorten hashes to 8 characters. It
+    /// applies to lines added to the grid after it is set.
+    ///
+    /// ```
+    /// use std::{cell::RefCell, rc::Rc};
+    /// use ttygrid::{add_line, grid, header, GridHeader};
+    ///
+    /// let hash = GridHeader::default()
+    ///     .set_text("hash")
+    ///     .set_transform(|s| s.chars().take(8).collect());
+    /// let mut grid = grid!(header!("name"), Rc::new(RefCell::new(hash))).unwrap();
+    /// add_line!(grid, "main", "0123456789abcdef0123");
+    ///
+    /// // the delimiter spans the shortened hash, not the original one
+    /// assert_eq!(
+    ///     grid.display().unwrap(),
+    ///     "name       hash\n-------------------\nmain       01234567\n"
+    /// );
+    /// ```
+    pub fn set_transform(mut self, transform: impl Fn(&str) -> String + 'static) -> Self {
+        self.transform = Some(Hook(Rc::new(transform)));
+        self
     }
 
-    pub fn select_all_headers(&mut self) {
-        self.selected = self.headers.clone()
+    /// Set a function which checks the contents of items belonging to this header when lines are
+    /// added to the grid, after the transform (see [GridHeader::set_transform]), such as that a
+    /// numeric column only holds numbers. Lines which fail are still added; the errors are
+    /// collected for each line and reported by [TTYGrid::content_errors] and
+    /// [TTYGrid::validate], so bad data is found before it is displayed. Lazy items (see
+    /// [TTYGrid::add_lazy_line]) and sparklines are not checked.
+    ///
+    /// ```
+    /// use std::{cell::RefCell, rc::Rc};
+    /// use ttygrid::{add_line, grid, header, GridHeader, ValidationError};
+    ///
+    /// let size = GridHeader::default().set_text("size").set_validator(|s| {
+    ///     s.parse::<u64>()
+    ///         .map(|_| ())
+    ///         .map_err(|_| format!("'{}' is not a number", s))
+    /// });
+    /// let mut grid = grid!(header!("name"), Rc::new(RefCell::new(size))).unwrap();
+    /// add_line!(grid, "a", 10);
+    /// add_line!(grid, "b", "ten");
+    ///
+    /// let errors = grid.content_errors();
+    /// assert_eq!(errors.len(), 1);
+    /// assert!(matches!(&errors[0], ValidationError::InvalidContent { line: 1, .. }));
+    /// assert_eq!(
+    ///     errors[0].to_string(),
+    ///     "line 2, column 'size': 'ten' is not a number"
+    /// );
+    /// ```
+    pub fn set_validator(
+        mut self,
+        validator: impl Fn(&str) -> std::result::Result<(), String> + 'static,
+    ) -> Self {
+        self.validator = Some(Hook(Rc::new(validator)));
+        self
     }
 
-    pub fn deselect_all_headers(&mut self) {
-        self.selected.0.clear()
+    /// Set the position this header lives within the column list. 0 is the first position. It only
+    /// breaks ties when headers of the same priority are compared; grids keep the order of their
+    /// selected headers themselves (see [TTYGrid::select]).
+    pub fn set_index(&mut self, idx: usize) {
+        self.index = Some(idx);
     }
 
-    fn set_grid_max_len(&mut self, len_map: &LengthMapper) -> Result<()> {
-        let mut cached_columns = Vec::new();
+    pub fn text(&self) -> &str {
+        &self.text
+    }
 
-        for (idx, header) in self.headers.0.iter_mut().enumerate() {
-            let max_len = len_map.max_len_for_column(&header.borrow())?;
-            header.borrow_mut().set_max_len(max_len);
-            cached_columns.insert(idx, header.borrow().max_len);
-        }
+    pub fn key(&self) -> &str {
+        self.key.as_deref().unwrap_or(&self.text)
+    }
 
-        for line in self.lines.iter_mut() {
-            for (idx, item) in line.0.iter_mut().enumerate() {
-                if let Some(column) = cached_columns.get(idx) {
-                    item.set_max_len(column.unwrap());
-                }
-            }
-        }
+    pub fn priority(&self) -> usize {
+        self.priority
+    }
 
-        Ok(())
+    /// Essential headers have a priority of at least [Priority::Essential], and must always be
+    /// displayed.
+    pub fn is_essential(&self) -> bool {
+        self.priority >= Priority::Essential.into()
     }
+}
 
-    fn determine_headers(&mut self) -> Result<()> {
-        let mut len_map = LengthMapper::default();
-        len_map.map_lines(self.lines.clone());
+/// HeaderId identifies a header by its position within a [TTYGrid]'s list of headers. The text
+/// of the header is carried along for reporting.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HeaderId {
+    index: usize,
+    key: String,
+    text: String,
+}
 
-        self.set_grid_max_len(&len_map)?; // this has to happen before any return occurs
-        let last = len_map.max_len_for_headers(self.headers.clone())?;
+impl HeaderId {
+    /// The position of the header within [TTYGrid::headers].
+    pub fn index(&self) -> usize {
+        self.index
+    }
 
-        if last <= self.width {
-            self.select_all_headers();
-            return Ok(());
-        }
+    pub fn key(&self) -> &str {
+        &self.key
+    }
 
-        let mut prio_map: Vec<(usize, (HeaderList, usize))> = Vec::new();
-        self.deselect_all_headers();
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
 
-        let mut len = self.headers.0.len();
+/// TerminalTooSmall is the error yielded when rendering a grid whose essential columns (see
+/// [Priority::Essential]) cannot fit in the terminal, or which cannot fit any column at all. It
+/// can be retrieved from the [anyhow::Error] with `downcast_ref`.
+///
+/// ```
+/// use ttygrid::{add_line, grid, header, test::FakeTerm, Priority, TerminalTooSmall};
+///
+/// let mut grid = grid!(
+///     header!("name", Priority::Essential),
+///     header!("description", Priority::Essential),
+///     header!("age")
+/// )
+/// .unwrap();
+/// add_line!(grid, "web-1", "the frontend server", "1d");
+/// assert_eq!(grid.minimum_width().unwrap(), 38);
+///
+/// grid.refresh_width(&FakeTerm::new(20, 10)).unwrap();
+/// let err = grid.display().unwrap_err();
+/// let too_small = err.downcast_ref::<TerminalTooSmall>().unwrap();
+/// assert_eq!((too_small.width, too_small.minimum_width), (20, 38));
+/// let essential = too_small
+///     .essential
+///     .iter()
+///     .map(|id| id.text())
+///     .collect::<Vec<_>>();
+/// assert_eq!(essential, vec!["name", "description"]);
+///
+/// grid.refresh_width(&FakeTerm::new(38, 10)).unwrap();
+/// assert!(grid.display().is_ok());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TerminalTooSmall {
+    /// The width of the terminal.
+    pub width: usize,
+    /// The width needed to display the narrowest layout.
+    pub minimum_width: usize,
+    /// The narrowest acceptable layout; see [TTYGrid::minimum_width].
+    pub narrowest_layout: Vec<HeaderId>,
+    /// The essential columns of the grid.
+    pub essential: Vec<HeaderId>,
+}
 
-        while len > 0 {
-            let mut headers = HeaderList::new();
-            for header in self.headers.0.iter().take(len) {
-                headers.0.push(header.clone())
-            }
+impl fmt::Display for TerminalTooSmall {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "your terminal is too small: {} columns are needed to display {}, \
+             but only {} are available",
+            self.minimum_width,
+            self.narrowest_layout
+                .iter()
+                .map(|h| h.text())
+                .collect::<Vec<&str>>()
+                .join(", "),
+            self.width
+        )
+    }
+}
 
-            let mut max_len = len_map.max_len_for_headers(headers.clone())?;
+impl std::error::Error for TerminalTooSmall {}
 
-            while max_len > self.width {
-                let mut new_headers = headers.clone();
-                let mut to_remove = None;
-                let mut lowest_prio_index = MAX;
+/// GridItem is the encapsulation of a piece of content. It is usually created by invoking
+/// [crate::add_line!] and is not instantiated directly.
+#[derive(Clone, Debug, Default)]
+pub struct GridItem {
+    header: SafeGridHeader,
+    contents: String,
+    max_len: Option<usize>,
+    sparkline: Option<Vec<f64>>,
+    trim: bool,
+    colors: Option<Colors>,
+    age: Option<AgeCell>,
+    spans: Option<Vec<StyledSpan>>,
+    lazy: Option<LazyContents>,
+    // the declared width of raw contents
+    raw: Option<usize>,
+    // the longest suffix of the column in the grid, for alignments which line up on a suffix
+    suffix_len: usize,
+    // whether the contents were made from invalid UTF-8 or control characters
+    lossy: bool,
+}
 
-                for (idx, header) in new_headers.0.iter().enumerate() {
-                    let priority = header.borrow().priority;
-                    if priority < lowest_prio_index {
-                        to_remove = Some(idx);
-                        lowest_prio_index = priority;
-                    }
-                }
+impl GridItem {
+    pub fn new(header: SafeGridHeader, contents: String) -> Self {
+        Self {
+            header,
+            contents,
+            max_len: None,
+            sparkline: None,
+            trim: false,
+            colors: None,
+            age: None,
+            spans: None,
+            lazy: None,
+            raw: None,
+            suffix_len: 0,
+            lossy: false,
+        }
+    }
+
+    /// Create an item whose contents are written verbatim, such as pre-formatted terminal art
+    /// with its own escape sequences. The item is measured as the width, which is what the
+    /// contents should take on the terminal, and is never aligned, clipped, wrapped, transformed
+    /// or abbreviated; it is only followed by the padding of its column. Redacted columns (see
+    /// [TTYGrid::set_redaction]) display the redacted contents as usual.
+    ///
+    /// ```
+    /// use ttygrid::{grid, header, GridItem, GridLine};
+    ///
+    /// let status = header!("status");
+    /// let mut grid = grid!(status.clone(), header!("name")).unwrap();
+    /// let mut line = GridLine::new();
+    /// line.push(GridItem::raw(status, "\x1b[32m●\x1b[0m".to_string(), 1));
+    /// line.push(GridItem::new(grid.headers()[1].clone(), "web".to_string()));
+    /// grid.add_line(line);
+    ///
+    /// let output = grid.display().unwrap();
+    /// assert!(output.lines().nth(2).unwrap().starts_with("\x1b[32m●\x1b[0m"));
+    /// ```
+    pub fn raw(header: SafeGridHeader, contents: String, width: usize) -> Self {
+        Self {
+            raw: Some(width),
+            ..Self::new(header, contents)
+        }
+    }
+
+    /// Create an item from bytes which may not be valid UTF-8, such as file names or process
+    /// arguments. Invalid sequences are displayed as the replacement character (`�`), as are
+    /// control characters, which would otherwise move the cursor or write escape sequences and
+    /// misalign the table; such items are flagged (see [GridItem::is_lossy]). Use
+    /// [GridItem::from_bytes_escaped] to display the bytes themselves.
+    ///
+    /// ```
+    /// use ttygrid::{header, GridItem};
+    ///
+    /// let item = GridItem::from_bytes(header!("name"), b"caf\xe9\x1b[2J.txt");
+    /// assert_eq!(item.contents(), "caf��[2J.txt");
+    /// assert!(item.is_lossy());
+    /// assert!(!GridItem::from_bytes(header!("name"), "café".as_bytes()).is_lossy());
+    /// ```
+    pub fn from_bytes(header: SafeGridHeader, bytes: &[u8]) -> Self {
+        Self::decoded(header, bytes, |_| char::REPLACEMENT_CHARACTER.to_string())
+    }
+
+    /// Create an item from bytes like [GridItem::from_bytes], displaying invalid sequences and
+    /// control characters as hex escapes such as `\x1b`, so the bytes can be told apart.
+    ///
+    /// ```
+    /// use ttygrid::{header, GridItem};
+    ///
+    /// let item = GridItem::from_bytes_escaped(header!("name"), b"caf\xe9\x1b[2J.txt");
+    /// assert_eq!(item.contents(), r"caf\xe9\x1b[2J.txt");
+    /// ```
+    pub fn from_bytes_escaped(header: SafeGridHeader, bytes: &[u8]) -> Self {
+        Self::decoded(header, bytes, |bytes| {
+            bytes.iter().map(|b| format!("\\x{:02x}", b)).collect()
+        })
+    }
+
+    // decodes the bytes, displaying invalid sequences and control characters as what the
+    // replacement yields for their bytes.
+    fn decoded(header: SafeGridHeader, bytes: &[u8], replace: impl Fn(&[u8]) -> String) -> Self {
+        let mut contents = String::with_capacity(bytes.len());
+        let mut lossy = false;
 
-                if let Some(to_remove) = to_remove {
-                    new_headers.0.remove(to_remove);
-                    max_len = len_map.max_len_for_headers(new_headers.clone())?;
-                    headers = new_headers;
+        for chunk in bytes.utf8_chunks() {
+            for c in chunk.valid().chars() {
+                if c.is_control() {
+                    let mut buf = [0; 4];
+                    contents.push_str(&replace(c.encode_utf8(&mut buf).as_bytes()));
+                    lossy = true;
                 } else {
-                    max_len = 0 // bury it
+                    contents.push(c);
                 }
             }
 
-            let index = headers.0.iter().fold(0, |acc, x| acc + x.borrow().priority);
-            prio_map.push((index, (headers, max_len)));
-            len -= 1;
+            if !chunk.invalid().is_empty() {
+                contents.push_str(&replace(chunk.invalid()));
+                lossy = true;
+            }
         }
 
-        if prio_map.is_empty() {
-            return Err(anyhow!("your terminal is too small"));
+        Self {
+            lossy,
+            ..Self::new(header, contents)
         }
+    }
 
-        prio_map.sort();
-
-        let (_, (max_headers, _)) = prio_map.iter().last().unwrap();
+    /// Whether the item was made from invalid UTF-8 or control characters, which are displayed
+    /// differently; see [GridItem::from_bytes].
+    pub fn is_lossy(&self) -> bool {
+        self.lossy
+    }
 
-        for (idx, header) in max_headers.0.iter().enumerate() {
-            self.select(header.clone(), idx);
+    /// Create an item which displays the values as a small bar chart, using block characters
+    /// scaled between the smallest and largest value. The chart needs one character per value,
+    /// and is stretched to fill the column if the column is wider.
+    ///
+    /// ```
+    /// use ttygrid::{assert_grid_eq, grid, header, GridItem, GridLine};
+    ///
+    /// let name = header!("name");
+    /// let cpu = header!("cpu");
+    /// let mut grid = grid!(name.clone(), cpu.clone()).unwrap();
+    /// grid.add_line(GridLine::from(vec![
+    ///     GridItem::new(name, "web-1".to_string()),
+    ///     GridItem::sparkline(cpu, &[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0]),
+    /// ]));
+    ///
+    /// assert_grid_eq!(
+    ///     grid,
+    ///     "
+    ///     name        cpu
+    ///     --------------------
+    ///     web-1       ▁▂▃▄▅▆▇█
+    ///     "
+    /// );
+    /// ```
+    pub fn sparkline(header: SafeGridHeader, values: &[f64]) -> Self {
+        Self {
+            header,
+            contents: render_sparkline(values, values.len(), Charset::default()),
+            max_len: None,
+            sparkline: Some(values.to_vec()),
+            trim: false,
+            colors: None,
+            age: None,
+            spans: None,
+            lazy: None,
+            raw: None,
+            suffix_len: 0,
+            lossy: false,
         }
+    }
 
-        Ok(())
+    /// Create an item whose contents are made of several differently colored spans, such as a
+    /// dim directory and a bright file name. The item is measured and aligned by the combined
+    /// text of the spans; see [StyledSpan].
+    pub fn spans(header: SafeGridHeader, spans: Vec<StyledSpan>) -> Self {
+        Self {
+            contents: spans.iter().map(|s| s.text.as_str()).collect(),
+            spans: Some(spans),
+            ..Self::new(header, String::new())
+        }
     }
 
-    /// Yield a string which is suitable for passing to [println!], but does not make any attempt
+    /// Create an item which displays how long ago the time was; see [AgeCell].
+    pub fn age(header: SafeGridHeader, time: std::time::SystemTime) -> Self {
+        let age = AgeCell::new(time);
+        Self {
+            contents: age.to_string(),
+            age: Some(age),
+            ..Self::new(header, String::new())
+        }
+    }
+
+    /// Set the colors of this item, which take precedence over any other colors; see [Style].
+    /// They are only used by [TTYGrid::write].
+    pub fn set_colors(&mut self, colors: Colors) {
+        self.colors = Some(colors)
+    }
+
+    pub fn colors(&self) -> Option<Colors> {
+        self.colors
+    }
+
+    pub fn contents(&self) -> &str {
+        &self.contents
+    }
+
+    pub fn header(&self) -> SafeGridHeader {
+        self.header.clone()
+    }
+
+    fn len(&self) -> usize {
+        match self.raw {
+            Some(width) => width + 1,
+            None => content_len(&self.contents),
+        }
+    }
+
+    fn set_max_len(&mut self, max_len: usize) {
+        self.max_len = Some(max_len)
+    }
+
+    // produces the contents of a lazy item.
+    fn materialize(&mut self) {
+        if let Some(lazy) = self.lazy.take() {
+            let header = self.header.borrow();
+            let mut contents = (lazy.0)(&header);
+            if let Some(transform) = &header.transform {
+                contents = (transform.0)(&contents);
+            }
+            drop(header);
+
+            self.contents = contents;
+        }
+    }
+
+    // pads the contents on the right so the point the alignment lines up on, such as the decimal
+    // point, lines up with a column whose longest suffix is suffix_len characters.
+    fn suffixed_contents(&self, alignment: Alignment, suffix_len: usize) -> String {
+        let pad = suffix_len.saturating_sub(crate::suffix_len(&self.contents, alignment));
+        format!("{}{}", self.contents, " ".repeat(pad))
+    }
+
+    // splits the displayed item into colored segments, one for each of its spans. The colors of
+    // the spans are layered over the colors of the cell.
+    fn segments(&self, colors: Colors) -> Vec<(Colors, String)> {
+        let text = self.to_string();
+        let spans = match &self.spans {
+            Some(spans) => spans,
+            None => return vec![(colors, text)],
+        };
+
+        // the contents may have been changed since the spans were set, e.g. by a tree prefix
+        // being added in front of them; only the spans which are still displayed are colored.
+        let styled: String = spans.iter().map(|s| s.text.as_str()).collect();
+        let start = match text.find(&self.contents) {
+            Some(start) if self.contents.ends_with(&styled) => {
+                start + self.contents.len() - styled.len()
+            }
+            _ => return vec![(colors, text)],
+        };
+
+        let mut ret = vec![(colors, text[..start].to_string())];
+        for span in spans {
+            let style = Style {
+                stripe: Some(colors),
+                cell: Some(span.colors),
+                ..Default::default()
+            };
+            ret.push((style.resolve(), span.text.clone()));
+        }
+        ret.push((colors, text[start + styled.len()..].to_string()));
+
+        ret.retain(|(_, text)| !text.is_empty());
+        ret
+    }
+}
+
+fn render_sparkline(values: &[f64], width: usize, charset: Charset) -> String {
+    let blocks = charset.gauge();
+
+    if values.is_empty() {
+        return " ".repeat(width);
+    }
+
+    let (min, max) = values
+        .iter()
+        .filter(|v| !v.is_nan())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+            (min.min(*v), max.max(*v))
+        });
+
+    (0..width)
+        .map(|i| values[i * values.len() / width])
+        .map(|v| {
+            if v.is_nan() {
+                ' '
+            } else if max > min {
+                let idx = ((v - min) / (max - min) * (blocks.len() - 1) as f64).round();
+                blocks[idx as usize]
+            } else {
+                blocks[0]
+            }
+        })
+        .collect()
+}
+
+impl fmt::Display for GridItem {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let header = self.header.borrow();
+        // items added since the last layout take the width of their column
+        let max_len = self.max_len.or(header.max_len).unwrap_or(self.len());
+        if let Some(width) = self.raw {
+            let pad = max_len.saturating_sub(width);
+            if self.trim {
+                return write!(formatter, "{}", self.contents);
+            }
+            return write!(formatter, "{}{}", self.contents, " ".repeat(pad));
+        }
+
+        let aligned = match header.alignment {
+            alignment @ (Alignment::Decimal | Alignment::Unit) => align(
+                &self.suffixed_contents(alignment, self.suffix_len),
+                max_len,
+                header.gap(),
+                Alignment::Right,
+            ),
+            alignment => align(&self.contents, max_len, header.gap(), alignment),
+        };
+        let aligned = match header.pad_char {
+            ' ' => aligned,
+            _ if self.contents.is_empty() => aligned,
+            pad => lead(&aligned, header.alignment, pad),
+        };
+
+        if self.trim {
+            write!(formatter, "{}", aligned.trim_end())
+        } else {
+            write!(formatter, "{}", aligned)
+        }
+    }
+}
+
+/// Usually constructed by [crate::grid!], this is the outer object of the whole library, all
+/// things are held by it in some form. Please review the impl for methods which can be used to
+/// adjust the properties of the grid once created.
+///
+/// Cloning a grid is shallow: the clone shares its headers with the original, so changes to a
+/// header, including the column widths calculated when either grid is displayed, are seen by
+/// both. Use [TTYGrid::deep_clone] for an independent copy.
+#[derive(Clone)]
+pub struct TTYGrid {
+    headers: HeaderList,
+    selected: HeaderList,
+    lines: Vec<GridLine>,
+    width: usize,
+    theme: Theme,
+    dedup: Vec<(usize, Dedup)>,
+    heatmaps: Vec<(usize, Heatmap)>,
+    column_colors: Vec<(usize, Colors)>,
+    on_layout_degraded: Option<DegradedCallback>,
+    sample: Sample,
+    label_provider: Option<LabelProvider>,
+    ragged: bool,
+    row_renderer: Option<RowRenderer>,
+    views: Vec<(String, Vec<usize>)>,
+    active_view: Option<String>,
+    tree_column: Option<usize>,
+    hidden: Vec<usize>,
+    shown: Vec<usize>,
+    uninformative: UninformativeColumns,
+    highlight_changes: Option<Colors>,
+    last_written: Option<Vec<GridLine>>,
+    changes: Option<GridDiff>,
+    reset: Reset,
+    trim_trailing_whitespace: bool,
+    width_quantum: usize,
+    overflow: OverflowPolicy,
+    key_column: usize,
+    overflow_tables: Vec<HeaderList>,
+    frozen: Vec<usize>,
+    column_offset: usize,
+    wrap: bool,
+    max_row_height: Option<usize>,
+    expand_rows: bool,
+    charset: Charset,
+    label_ellipsis: Option<String>,
+    table_align: TableAlign,
+    show_header: bool,
+    word_break: WordBreak,
+    sort: Vec<(usize, SortOrder)>,
+    // the positions of the lines in the order they are displayed, when sorted.
+    order: Vec<usize>,
+    comparators: Vec<(usize, Comparator)>,
+    redactions: Vec<(usize, RedactionPolicy)>,
+    filters: Vec<(usize, String)>,
+    scroll: Option<ScrollPosition>,
+    stripe_by: Option<usize>,
+    filter_display: FilterDisplay,
+    plain_buffer: Vec<u8>,
+    repeat_header: Option<usize>,
+    legend: Vec<(String, String)>,
+    status: Option<(String, Colors)>,
+    header_decoration: Decoration,
+    underline_header: bool,
+    indent: String,
+    markers: Vec<(usize, MarkerColumn)>,
+    vocabularies: Vec<(usize, Vocabulary)>,
+    icons: Vec<(usize, IconColumn, SavedLayout)>,
+    observers: Vec<Rc<dyn GridObserver>>,
+    memory_limit: Option<usize>,
+    memory_used: usize,
+    skipped: usize,
+    skip_marker: Option<usize>,
+    quote_style: QuoteStyle,
+    color_depth: ColorDepth,
+    sort_indicator: bool,
+    // the longest suffix of each column aligned on a suffix, by the position of its header
+    suffixes: Vec<(usize, usize)>,
+    // the width added to each column by the width quantum, by the position of its header
+    rounding: Vec<(usize, usize)>,
+    flush_right: Option<usize>,
+    infer_headers: bool,
+}
+
+/// WordBreak controls how words which do not fit the maximum width of their column are broken
+/// when wrapping; see [TTYGrid::set_word_break].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum WordBreak {
+    /// Break long words at the width of the column. This is the default.
+    #[default]
+    Anywhere,
+    /// Break words after their hyphens where possible, and end the lines of words which are
+    /// broken elsewhere with a hyphen.
+    Hyphenate,
+}
+
+/// Decoration changes the case of the header labels when they are displayed, without changing
+/// the headers; see [TTYGrid::set_header_decoration].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Decoration {
+    /// Display the labels as they are. This is the default.
+    #[default]
+    None,
+    /// Display the labels in upper case.
+    Upper,
+    /// Capitalize the first letter of every word, separated by spaces, `_` or `-`, and lower the
+    /// case of the rest.
+    Title,
+}
+
+impl Decoration {
+    /// Apply the decoration to the label.
+    pub fn apply(&self, label: &str) -> String {
+        match self {
+            Decoration::None => label.to_string(),
+            Decoration::Upper => label.to_uppercase(),
+            Decoration::Title => {
+                let mut ret = String::with_capacity(label.len());
+                let mut start = true;
+                for c in label.chars() {
+                    if start {
+                        ret.extend(c.to_uppercase());
+                    } else {
+                        ret.extend(c.to_lowercase());
+                    }
+                    start = c.is_whitespace() || c == '_' || c == '-';
+                }
+                ret
+            }
+        }
+    }
+}
+
+/// ColorChoice controls whether terminal colors are written to files; see [TTYGrid::write_file].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    /// Write plain text, without any escape sequences. This is the default.
+    #[default]
+    Never,
+    /// Write the colors like [TTYGrid::write], such as for files viewed with `less -R`.
+    Always,
+}
+
+/// OverflowPolicy controls what happens to the columns which do not fit in the terminal; see
+/// [TTYGrid::set_overflow_policy].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum OverflowPolicy {
+    /// Do not display them. This is the default.
+    #[default]
+    Drop,
+    /// Display them in one or more additional tables below the first, each of which repeats the
+    /// key column (see [TTYGrid::set_key_column]), so no data is hidden.
+    Stack,
+}
+
+/// TableAlign positions a table which is narrower than the terminal; see
+/// [TTYGrid::set_table_alignment]. The delimiter below the header always spans the table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum TableAlign {
+    /// Start the table at the left edge of the terminal. This is the default.
+    #[default]
+    Left,
+    /// Center the table.
+    Center,
+    /// End the table at the right edge of the terminal.
+    Right,
+}
+
+/// Reset controls when [TTYGrid::write] resets the terminal colors; see
+/// [TTYGrid::set_reset_policy].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Reset {
+    /// Reset the colors once the table is written, so they do not bleed into later output. This
+    /// is the default.
+    #[default]
+    Table,
+    /// Reset the colors at the end of every line, before the newline, which keeps background
+    /// colors from extending to the edge of the terminal.
+    Line,
+    /// Never reset the colors.
+    Never,
+}
+
+/// Sample controls which lines are measured when calculating column widths; see
+/// [TTYGrid::set_width_sampling]. Items of lines which are not measured are clipped to the width
+/// of their column, with an ellipsis, if they are longer than the items of the measured lines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Sample {
+    /// Measure every line. This is the default.
+    #[default]
+    All,
+    /// Measure the first n lines. Measuring no lines would leave the columns without widths, so
+    /// 0 measures every line.
+    First(usize),
+    /// Measure n lines picked at random. The same lines are picked for the same number of lines,
+    /// so repeated renders are stable. Like [Sample::First], 0 measures every line.
+    Random(usize),
+}
+
+impl Sample {
+    // whether every line is measured.
+    fn measures_all(&self) -> bool {
+        matches!(self, Sample::All | Sample::First(0) | Sample::Random(0))
+    }
+
+    fn indices(&self, len: usize) -> Box<dyn Iterator<Item = usize>> {
+        match *self {
+            Sample::All | Sample::First(0) | Sample::Random(0) => Box::new(0..len),
+            Sample::First(n) => Box::new(0..n.min(len)),
+            Sample::Random(n) if n >= len => Box::new(0..len),
+            Sample::Random(n) => {
+                // Floyd's algorithm over a xorshift generator; n distinct indices in 0..len
+                let mut state = (len as u64) ^ 0x9e37_79b9_7f4a_7c15;
+                let mut next = move || {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    state
+                };
+
+                let mut picked = std::collections::BTreeSet::new();
+                for j in (len - n)..len {
+                    let t = (next() % (j as u64 + 1)) as usize;
+                    if !picked.insert(t) {
+                        picked.insert(j);
+                    }
+                }
+
+                Box::new(picked.into_iter())
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Heatmap {
+    min: f64,
+    max: f64,
+    scale: ColorScale,
+}
+
+impl Heatmap {
+    fn color_for(&self, contents: &str) -> Option<Color> {
+        let value = leading_number(contents)?;
+        let range = self.max - self.min;
+        let position = if range == 0.0 {
+            0.0
+        } else {
+            (value - self.min) / range
+        };
+
+        Some(self.scale.color_for(position))
+    }
+}
+
+// parses the number at the start of the string, so "12ms" and "40%" are measured as 12 and 40.
+fn leading_number(s: &str) -> Option<f64> {
+    let s = s.trim();
+    let end = s
+        .char_indices()
+        .find(|(idx, c)| {
+            !(c.is_ascii_digit() || *c == '.' || (*idx == 0 && (*c == '-' || *c == '+')))
+        })
+        .map(|(idx, _)| idx)
+        .unwrap_or(s.len());
+
+    s[..end].parse().ok()
+}
+
+/// Dedup controls what replaces a repeated value in a column configured with
+/// [TTYGrid::dedup_display].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dedup {
+    /// Leave the cell blank.
+    Blank,
+    /// Print a ditto mark (`"`) in place of the value.
+    Ditto,
+}
+
+impl Dedup {
+    fn mark(&self) -> &'static str {
+        match self {
+            Dedup::Blank => "",
+            Dedup::Ditto => "\"",
+        }
+    }
+}
+
+impl TTYGrid {
+    /// Create a grid of the headers, laid out for the width of the terminal.
+    pub fn new(headers: Vec<SafeGridHeader>) -> Result<Self> {
+        Self::with_width_source(headers, &Terminal)
+    }
+
+    /// Create a grid of the headers, laid out for the width the source yields, such as a
+    /// [test::FakeTerm].
+    pub fn with_width_source(
+        headers: Vec<SafeGridHeader>,
+        source: &impl WidthSource,
+    ) -> Result<Self> {
+        let width = source.width()?;
+
+        Ok(Self {
+            selected: HeaderList::new(),
+            headers: HeaderList(headers),
+            lines: Vec::new(),
+            width,
+            theme: Theme::default(),
+            dedup: Vec::new(),
+            heatmaps: Vec::new(),
+            column_colors: Vec::new(),
+            on_layout_degraded: None,
+            sample: Sample::All,
+            label_provider: None,
+            ragged: false,
+            row_renderer: None,
+            views: Vec::new(),
+            active_view: None,
+            tree_column: None,
+            hidden: Vec::new(),
+            shown: Vec::new(),
+            uninformative: UninformativeColumns::Keep,
+            highlight_changes: None,
+            last_written: None,
+            changes: None,
+            reset: Reset::Table,
+            trim_trailing_whitespace: true,
+            width_quantum: 0,
+            overflow: OverflowPolicy::Drop,
+            key_column: 0,
+            overflow_tables: Vec::new(),
+            frozen: Vec::new(),
+            column_offset: 0,
+            wrap: false,
+            max_row_height: None,
+            expand_rows: false,
+            charset: Charset::Unicode,
+            label_ellipsis: None,
+            table_align: TableAlign::Left,
+            show_header: true,
+            word_break: WordBreak::Anywhere,
+            sort: Vec::new(),
+            order: Vec::new(),
+            comparators: Vec::new(),
+            redactions: Vec::new(),
+            filters: Vec::new(),
+            scroll: None,
+            stripe_by: None,
+            filter_display: FilterDisplay::Hide,
+            plain_buffer: Vec::new(),
+            repeat_header: None,
+            legend: Vec::new(),
+            status: None,
+            header_decoration: Decoration::None,
+            underline_header: false,
+            indent: "  ".to_string(),
+            markers: Vec::new(),
+            vocabularies: Vec::new(),
+            icons: Vec::new(),
+            observers: Vec::new(),
+            memory_limit: None,
+            memory_used: 0,
+            skipped: 0,
+            skip_marker: None,
+            quote_style: QuoteStyle::Minimal,
+            color_depth: ColorDepth::TrueColor,
+            sort_indicator: false,
+            suffixes: Vec::new(),
+            rounding: Vec::new(),
+            flush_right: None,
+            infer_headers: false,
+        })
+    }
+
+    /// Construct a grid from a [GridConfig], typically loaded from a configuration file.
+    pub fn from_config(config: GridConfig) -> Result<Self> {
+        let mut grid = Self::new(
+            config
+                .headers
+                .into_iter()
+                .map(|h| Rc::new(RefCell::new(h)))
+                .collect(),
+        )?;
+        grid.set_theme(config.theme);
+        grid.set_layout(&config.layout)?;
+        Ok(grid)
+    }
+
+    /// Yield the [GridConfig] describing this grid's headers, theme and layout.
+    pub fn config(&self) -> GridConfig {
+        let key = |idx: &usize| self.headers.0[*idx].borrow().key().to_string();
+
+        GridConfig {
+            headers: self.headers.0.iter().map(|h| h.borrow().clone()).collect(),
+            theme: self.theme,
+            layout: LayoutConfig {
+                hidden: self.hidden.iter().map(key).collect(),
+                shown: self.shown.iter().map(key).collect(),
+                frozen: self.frozen.iter().map(key).collect(),
+                views: self
+                    .views
+                    .iter()
+                    .map(|(name, view)| (name.clone(), view.iter().map(key).collect()))
+                    .collect(),
+                active_view: self.active_view.clone(),
+                sort: self
+                    .sort
+                    .iter()
+                    .map(|(idx, order)| (key(idx), *order))
+                    .collect(),
+                overflow: self.overflow,
+                key_column: (self.key_column < self.headers.len()).then(|| key(&self.key_column)),
+                table_align: self.table_align,
+            },
+        }
+    }
+
+    // applies the layout of a config; columns are looked up by their keys.
+    fn set_layout(&mut self, layout: &LayoutConfig) -> Result<()> {
+        let indexes = |grid: &TTYGrid, keys: &[String]| {
+            keys.iter()
+                .map(|key| grid.column_index(key))
+                .collect::<Result<Vec<_>>>()
+        };
+
+        for idx in indexes(self, &layout.hidden)? {
+            self.set_hidden_index(idx, true);
+        }
+        for idx in indexes(self, &layout.shown)? {
+            self.set_hidden_index(idx, false);
+        }
+        self.frozen = indexes(self, &layout.frozen)?;
+        for (name, keys) in &layout.views {
+            let mut view = indexes(self, keys)?;
+            view.sort_unstable();
+            view.dedup();
+            self.views.push((name.clone(), view));
+        }
+        if let Some(name) = &layout.active_view {
+            self.set_active_view(name)?;
+        }
+        self.sort = layout
+            .sort
+            .iter()
+            .map(|(key, order)| Ok((self.column_index(key)?, *order)))
+            .collect::<Result<_>>()?;
+        self.overflow = layout.overflow;
+        if let Some(key) = &layout.key_column {
+            self.key_column = self.column_index(key)?;
+        }
+        self.table_align = layout.table_align;
+        Ok(())
+    }
+
+    /// Create a grid of two columns, for "describe" style detail views: the keys, which are
+    /// aligned to the right, and their values. The header line is not displayed.
+    ///
+    /// ```
+    /// use ttygrid::TTYGrid;
+    ///
+    /// let mut grid = TTYGrid::key_value(vec![
+    ///     ("name".to_string(), "web-1".to_string()),
+    ///     ("status".to_string(), "running".to_string()),
+    /// ])
+    /// .unwrap();
+    ///
+    /// let lines = grid
+    ///     .display()
+    ///     .unwrap()
+    ///     .lines()
+    ///     .map(|l| l.trim_start().to_string())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(lines.len(), 2);
+    /// assert!(lines[0].starts_with("name"));
+    /// assert!(lines[1].ends_with("running"));
+    /// ```
+    pub fn key_value(pairs: impl IntoIterator<Item = (String, String)>) -> Result<Self> {
+        let key = Rc::new(RefCell::new(
+            GridHeader::default()
+                .set_text("key")
+                .set_tier(Priority::Essential)
+                .set_alignment(Alignment::Right),
+        ));
+        let value = Rc::new(RefCell::new(
+            GridHeader::default()
+                .set_text("value")
+                .set_tier(Priority::Important),
+        ));
+
+        let mut grid = Self::new(vec![key.clone(), value.clone()])?;
+        grid.set_show_header(false);
+
+        for (k, v) in pairs {
+            grid.add_line(GridLine::from(vec![
+                GridItem::new(key.clone(), k),
+                GridItem::new(value.clone(), v),
+            ]));
+        }
+
+        Ok(grid)
+    }
+
+    /// Lay the grid out for the width the source yields from now on, such as after the terminal
+    /// was resized.
+    pub fn refresh_width(&mut self, source: &impl WidthSource) -> Result<()> {
+        self.width = source.width()?;
+        Ok(())
+    }
+
+    /// Display the header line and the delimiter again before every n lines, so long tables
+    /// scrolled in a terminal remain readable. This applies to [TTYGrid::display],
+    /// [TTYGrid::write] and [TTYGrid::write_plain], not to the [Pager], which always displays the
+    /// header. [None], the default, displays the header once.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header};
+    ///
+    /// let mut grid = grid!(header!("name")).unwrap();
+    /// for name in ["a", "b", "c", "d", "e"] {
+    ///     add_line!(grid, name);
+    /// }
+    /// grid.set_repeat_header_every(Some(2));
+    ///
+    /// let output = grid.display().unwrap();
+    /// assert_eq!(output.lines().filter(|l| *l == "name").count(), 3);
+    /// ```
+    pub fn set_repeat_header_every(&mut self, n: Option<usize>) {
+        self.repeat_header = n
+    }
+
+    /// Display the header line and the delimiter below it, which spans the columns laid out, so
+    /// it ends with the table rather than the terminal. This is on by default.
+    pub fn set_show_header(&mut self, show: bool) {
+        self.show_header = show
+    }
+
+    // the number of lines displayed above the content.
+    fn header_rows(&self) -> usize {
+        if self.show_header {
+            2
+        } else {
+            0
+        }
+    }
+
+    /// Clone the grid along with its headers, so that the clone can be changed and displayed
+    /// without affecting this grid. Items of the clone refer to the clone's headers.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header};
+    ///
+    /// let mut grid = grid!(header!("name")).unwrap();
+    /// add_line!(grid, "one");
+    ///
+    /// let clone = grid.deep_clone();
+    /// clone.headers()[0].borrow_mut().set_max_len(20);
+    ///
+    /// assert!(!std::rc::Rc::ptr_eq(&grid.headers()[0], &clone.headers()[0]));
+    /// assert!(grid.headers()[0].borrow().max_len().is_none());
+    /// ```
+    pub fn deep_clone(&self) -> Self {
+        let mut ret = self.clone();
+        let headers = self
+            .headers
+            .iter()
+            .map(|h| Rc::new(RefCell::new(h.borrow().clone())))
+            .collect::<HeaderList>();
+
+        let remap = |header: &SafeGridHeader| match self.header_index(header) {
+            Ok(idx) => headers[idx].clone(),
+            Err(_) => Rc::new(RefCell::new(header.borrow().clone())),
+        };
+
+        let remap_list = |list: &HeaderList| list.iter().map(remap).collect::<HeaderList>();
+        let remap_lines = |lines: &Vec<GridLine>| {
+            lines
+                .iter()
+                .map(|line| {
+                    let mut line = line.clone();
+                    for item in line.items.iter_mut() {
+                        item.header = remap(&item.header);
+                    }
+                    line
+                })
+                .collect::<Vec<GridLine>>()
+        };
+
+        ret.selected = remap_list(&self.selected);
+        ret.overflow_tables = self.overflow_tables.iter().map(remap_list).collect();
+        ret.lines = remap_lines(&self.lines);
+        ret.last_written = self.last_written.as_ref().map(remap_lines);
+        ret.headers = headers;
+        ret
+    }
+
+    /// Sets all colors at once.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme
+    }
+
+    pub fn theme(&self) -> Theme {
+        self.theme
+    }
+
+    /// Sets the delimiter color; the dashes between the header and the content.
+    pub fn set_delimiter_color(&mut self, colors: Colors) {
+        self.theme.delimiter = colors
+    }
+
+    /// Sets the header color.
+    pub fn set_header_color(&mut self, colors: Colors) {
+        self.theme.header = colors
+    }
+
+    /// Sets the primary color; colors will alternate between primary and secondary per row as the
+    /// table is built.
+    pub fn set_primary_color(&mut self, colors: Colors) {
+        self.theme.primary = colors
+    }
+
+    /// Sets the secondary color; colors will alternate between primary and secondary per row as
+    /// the table is built.
+    pub fn set_secondary_color(&mut self, colors: Colors) {
+        self.theme.secondary = colors
+    }
+
+    pub fn add_line(&mut self, item: GridLine) {
+        let item = match self.header_row(item) {
+            Some(item) => item,
+            None => return,
+        };
+
+        self.lines.push(item.transformed());
+        self.lines_added(self.lines.len() - 1..self.lines.len());
+    }
+
+    /// Add every line of the iterator, like [TTYGrid::add_line]. Space is reserved for the
+    /// lines the iterator reports up front, so large tables are loaded without growing the list
+    /// of lines repeatedly.
+    ///
+    /// ```
+    /// use ttygrid::{grid, header};
+    ///
+    /// let mut grid = grid!(header!("name"), header!("size")).unwrap();
+    /// let lines = (0..1000)
+    ///     .map(|i| grid.line_from(vec![format!("file-{}", i), i.to_string()]))
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    ///
+    /// grid.extend_lines(lines);
+    /// assert_eq!(grid.lines().len(), 1000);
+    /// ```
+    pub fn extend_lines(&mut self, lines: impl IntoIterator<Item = GridLine>) {
+        let mut lines = lines.into_iter();
+        let inferred = self.infer_headers;
+        if inferred {
+            if let Some(line) = lines.next() {
+                self.header_row(line);
+            }
+        }
+
+        let start = self.lines.len();
+        self.lines.reserve(lines.size_hint().0);
+        self.lines.extend(lines.map(GridLine::transformed));
+
+        // the lines were made for the headers the first line replaced
+        if inferred {
+            for line in self.lines[start..].iter_mut() {
+                line.rebind(&self.headers);
+            }
+        }
+        self.lines_added(start..self.lines.len());
+    }
+
+    /// Reserve space for at least this many more lines, for callers which add a known number of
+    /// lines one at a time.
+    pub fn reserve_lines(&mut self, additional: usize) {
+        self.lines.reserve(additional)
+    }
+
+    /// Add a line whose contents are produced by the callback, which is given the header of each
+    /// item, only when the item's column is displayed. Columns which are expensive to format
+    /// cost nothing when they are dropped to fit the terminal. Until they are produced, items are
+    /// measured as their header's minimum size (see [GridHeader::set_min_size]), so expensive
+    /// columns should set one close to their expected width.
+    ///
+    /// ```
+    /// use std::{cell::RefCell, rc::Rc};
+    /// use ttygrid::{assert_grid_eq, grid, header, GridHeader};
+    ///
+    /// let mut grid = grid!(header!("name"), header!("size"), header!("debug", hidden = true))
+    ///     .unwrap();
+    /// let produced = Rc::new(RefCell::new(Vec::new()));
+    /// let log = produced.clone();
+    /// grid.add_lazy_line(move |header: &GridHeader| {
+    ///     log.borrow_mut().push(header.text().to_string());
+    ///     format!("{}-value", header.text())
+    /// });
+    ///
+    /// assert_grid_eq!(
+    ///     grid,
+    ///     "
+    ///     name             size
+    ///     ---------------------------
+    ///     name-value       size-value
+    ///     "
+    /// );
+    /// // the hidden column is never produced
+    /// assert_eq!(*produced.borrow(), vec!["name", "size"]);
+    /// ```
+    pub fn add_lazy_line(&mut self, contents: impl Fn(&GridHeader) -> String + 'static) {
+        let contents: Rc<dyn Fn(&GridHeader) -> String> = Rc::new(contents);
+
+        let line = self
+            .headers
+            .iter()
+            .map(|header| GridItem {
+                lazy: Some(Hook(contents.clone())),
+                ..GridItem::new(header.clone(), String::new())
+            })
+            .collect::<GridLine>();
+
+        if let Some(line) = self.header_row(line) {
+            self.lines.push(line);
+            self.lines_added(self.lines.len() - 1..self.lines.len());
+        }
+    }
+
+    /// Add a line from its contents, in the order of the headers. This is what
+    /// [crate::try_add_line!] uses. Unless the grid is ragged, the number of items must be
+    /// equal to the number of headers.
+    pub fn add_contents(&mut self, contents: Vec<String>) -> Result<()> {
+        // the labels need not match the headers they replace
+        let line = if self.infer_headers {
+            contents
+                .into_iter()
+                .map(|contents| GridItem::new(Default::default(), contents))
+                .collect()
+        } else {
+            self.line_from(contents)?
+        };
+        self.add_line(line);
+        Ok(())
+    }
+
+    /// Create a line from its contents, in the order of the headers, without adding it to the
+    /// grid. The same rules as [TTYGrid::add_contents] apply.
+    pub fn line_from(&self, mut contents: Vec<String>) -> Result<GridLine> {
+        let len = self.headers.len();
+
+        if contents.len() != len {
+            if !self.ragged {
+                return Err(anyhow!(
+                    "ttygrid panic: content items must equal the number of headers"
+                ));
+            }
+
+            if len == 0 {
+                return Err(anyhow!("grid has no headers"));
+            }
+
+            if contents.len() > len {
+                let overflow = contents.split_off(len - 1).join(" ");
+                contents.push(overflow);
+            } else {
+                contents.resize(len, String::new());
+            }
+        }
+
+        Ok(contents
+            .into_iter()
+            .zip(self.headers.0.iter())
+            .map(|(item, header)| GridItem::new(header.clone(), item))
+            .collect())
+    }
+
+    /// In ragged mode, lines added with [TTYGrid::add_contents] or [crate::add_line!] may have
+    /// fewer items than there are headers, in which case the trailing columns are left empty; or
+    /// more, in which case the extra items are joined with spaces into the last column.
+    ///
+    /// ```
+    /// use ttygrid::{assert_grid_eq, grid, header, try_add_line};
+    ///
+    /// let mut grid = grid!(header!("name"), header!("state"), header!("message")).unwrap();
+    /// assert!(try_add_line!(grid, "web-1").is_err());
+    ///
+    /// grid.set_ragged(true);
+    /// try_add_line!(grid, "web-1").unwrap();
+    /// try_add_line!(grid, "web-2", "failed", "exit", "code", "1").unwrap();
+    /// assert_grid_eq!(
+    ///     grid,
+    ///     "
+    ///     name        state        message
+    ///     ------------------------------------
+    ///     web-1
+    ///     web-2       failed       exit code 1
+    ///     "
+    /// );
+    /// ```
+    pub fn set_ragged(&mut self, ragged: bool) {
+        self.ragged = ragged
+    }
+
+    pub fn is_ragged(&self) -> bool {
+        self.ragged
+    }
+
+    /// Treat the next line added, by any of the methods adding lines such as
+    /// [TTYGrid::add_contents], [crate::add_line!] or [TTYGrid::extend_lines], as the labels of
+    /// the columns instead of a line, like the first row of a CSV file, which is handy for tools
+    /// dumping data whose columns are not known up front. The headers of the grid are replaced by
+    /// headers of the labels, which are prioritized left to right, so the rightmost columns are
+    /// dropped first to fit the terminal.
+    ///
+    /// Settings of columns made before the headers are replaced, such as sorting or hidden
+    /// columns, apply to the new headers at their positions; those of positions the new headers
+    /// do not have are dropped. Lines added before are kept, with their items in the new columns
+    /// at their positions, and are cut or filled with empty items to the number of labels.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, SortOrder, TTYGrid};
+    ///
+    /// let mut grid = TTYGrid::new(Vec::new()).unwrap();
+    /// grid.infer_headers_from_first_row(true);
+    /// for row in "name,size,owner\nweb-1,10,root".lines() {
+    ///     grid.add_contents(row.split(',').map(String::from).collect()).unwrap();
+    /// }
+    ///
+    /// assert_eq!(grid.column_names(), vec!["name", "size", "owner"]);
+    /// assert_eq!(grid.lines().len(), 1);
+    /// assert!(grid.display().unwrap().starts_with("name        size     owner"));
+    ///
+    /// // a first row narrower than the headers drops the settings of the columns it lacks
+    /// let mut grid = grid!(header!("a"), header!("b"), header!("c")).unwrap();
+    /// grid.sort_spec([("c", SortOrder::Asc)]).unwrap();
+    /// add_line!(grid, "1", "2", "3");
+    /// grid.infer_headers_from_first_row(true);
+    /// add_line!(grid, "name", "size");
+    /// add_line!(grid, "web-1", "10");
+    ///
+    /// assert_eq!(grid.column_names(), vec!["name", "size"]);
+    /// let output = grid.display().unwrap();
+    /// assert_eq!(output.lines().nth(2), Some("1           2"));
+    /// assert_eq!(output.lines().nth(3), Some("web-1       10"));
+    ///
+    /// // the first of several lines added at once is taken as well
+    /// let mut grid = grid!(header!("a")).unwrap();
+    /// grid.infer_headers_from_first_row(true);
+    /// let lines = ["name", "web-1"].iter().map(|c| grid.line_from(vec![c.to_string()]).unwrap());
+    /// grid.extend_lines(lines.collect::<Vec<_>>());
+    /// assert_eq!(grid.column_names(), vec!["name"]);
+    /// assert_eq!(grid.display().unwrap(), "name\n-----\nweb-1\n");
+    /// ```
+    pub fn infer_headers_from_first_row(&mut self, infer: bool) {
+        self.infer_headers = infer
+    }
+
+    /// Whether the next line added is taken for the labels of the columns; see
+    /// [TTYGrid::infer_headers_from_first_row].
+    pub fn infers_headers(&self) -> bool {
+        self.infer_headers
+    }
+
+    // takes the line for the labels of the columns when headers are inferred, yielding it
+    // otherwise. Every method adding lines goes through this.
+    fn header_row(&mut self, mut line: GridLine) -> Option<GridLine> {
+        if !self.infer_headers {
+            return Some(line);
+        }
+
+        for item in line.items.iter_mut() {
+            item.materialize();
+        }
+        self.replace_headers(line.items.into_iter().map(|item| item.contents).collect());
+        None
+    }
+
+    // replaces the headers with headers of the labels, prioritized left to right. Settings of
+    // positions past the new headers are dropped, and the lines are moved to the new headers.
+    fn replace_headers(&mut self, labels: Vec<String>) {
+        let len = labels.len();
+        self.headers = labels
+            .into_iter()
+            .enumerate()
+            .map(|(idx, text)| {
+                Rc::new(RefCell::new(
+                    GridHeader::default().set_text(text).set_priority(len - idx),
+                ))
+            })
+            .collect();
+        self.selected = HeaderList::new();
+        self.overflow_tables.clear();
+        self.column_offset = 0;
+        self.infer_headers = false;
+
+        self.dedup.retain(|(idx, _)| *idx < len);
+        self.heatmaps.retain(|(idx, _)| *idx < len);
+        self.column_colors.retain(|(idx, _)| *idx < len);
+        self.sort.retain(|(idx, _)| *idx < len);
+        self.comparators.retain(|(idx, _)| *idx < len);
+        self.redactions.retain(|(idx, _)| *idx < len);
+        self.filters.retain(|(idx, _)| *idx < len);
+        self.markers.retain(|(idx, _)| *idx < len);
+        self.vocabularies.retain(|(idx, _)| *idx < len);
+        self.icons.retain(|(idx, _, _)| *idx < len);
+        self.suffixes.retain(|(idx, _)| *idx < len);
+        self.rounding.retain(|(idx, _)| *idx < len);
+        self.hidden.retain(|idx| *idx < len);
+        self.shown.retain(|idx| *idx < len);
+        self.frozen.retain(|idx| *idx < len);
+        for (_, view) in self.views.iter_mut() {
+            view.retain(|idx| *idx < len);
+        }
+        self.tree_column = self.tree_column.filter(|idx| *idx < len);
+        self.stripe_by = self.stripe_by.filter(|idx| *idx < len);
+        self.flush_right = self.flush_right.filter(|idx| *idx < len);
+        if self.key_column >= len {
+            self.key_column = 0;
+        }
+
+        // the previous lines are compared by position, as they were written
+        self.last_written = None;
+        self.changes = None;
+
+        for line in self.lines.iter_mut() {
+            line.rebind(&self.headers);
+        }
+    }
+
+    /// Insert a line at the position, shifting all lines after it down.
+    pub fn insert_line(&mut self, idx: usize, line: GridLine) -> Result<()> {
+        if idx > self.lines.len() {
+            return Err(anyhow!(
+                "cannot insert line at {}, grid only has {} lines",
+                idx,
+                self.lines.len()
+            ));
+        }
+
+        if let Some(line) = self.header_row(line) {
+            self.lines.insert(idx, line.transformed());
+            self.order.clear();
+            self.lines_added(idx..idx + 1);
+        }
+        Ok(())
+    }
+
+    /// Remove the line at the position, returning it.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header};
+    ///
+    /// let mut grid = grid!(header!("name")).unwrap();
+    /// add_line!(grid, "web-1");
+    /// add_line!(grid, "web-3");
+    ///
+    /// let line = grid.line_from(vec!["web-2".to_string()]).unwrap();
+    /// grid.insert_line(1, line).unwrap();
+    /// let removed = grid.remove_line(0).unwrap();
+    /// assert_eq!(removed.items()[0].contents(), "web-1");
+    ///
+    /// let names = grid
+    ///     .lines()
+    ///     .iter()
+    ///     .map(|line| line.items()[0].contents())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(names, vec!["web-2", "web-3"]);
+    /// assert!(grid.remove_line(2).is_err());
+    /// ```
+    pub fn remove_line(&mut self, idx: usize) -> Result<GridLine> {
+        if idx >= self.lines.len() {
+            return Err(anyhow!("no line at {}", idx));
+        }
+
+        let line = self.lines.remove(idx);
+        self.order.clear();
+        self.memory_used = self.memory_used.saturating_sub(line.memory_size());
+        Ok(line)
+    }
+
+    /// Hide the line at the position, or show it again. Hidden lines stay in the grid, so the
+    /// positions of the other lines do not change, but they are not measured, displayed or
+    /// included in exports of the displayed columns ([ExportColumns::Displayed]). This allows
+    /// collapsing and expanding lines, such as the children in a tree.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header};
+    ///
+    /// let mut grid = grid!(header!("name")).unwrap();
+    /// add_line!(grid, "parent");
+    /// add_line!(grid, "a-very-long-child");
+    ///
+    /// grid.set_line_hidden(1, true).unwrap();
+    /// assert_eq!(grid.hidden_count(), 1);
+    /// let output = grid.display().unwrap();
+    /// assert_eq!(output.lines().skip(2).collect::<Vec<&str>>(), vec!["parent"]);
+    /// ```
+    pub fn set_line_hidden(&mut self, idx: usize, hidden: bool) -> Result<()> {
+        match self.lines.get_mut(idx) {
+            Some(line) => {
+                line.hidden = hidden;
+                Ok(())
+            }
+            None => Err(anyhow!("no line at {}", idx)),
+        }
+    }
+
+    /// Whether the line at the position is hidden; see [TTYGrid::set_line_hidden].
+    pub fn is_line_hidden(&self, idx: usize) -> bool {
+        self.lines.get(idx).is_some_and(|line| line.hidden)
+    }
+
+    /// The number of hidden lines.
+    pub fn hidden_count(&self) -> usize {
+        self.lines.iter().filter(|line| line.hidden).count()
+    }
+
+    // the positions of the lines which are displayed, in the order they are displayed: not
+    // hidden, and not filtered out.
+    fn visible_lines(&self) -> Vec<usize> {
+        self.line_order()
+            .filter(|idx| self.line_shown(*idx))
+            .collect()
+    }
+
+    /// Replace the line at the position, returning the old line.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, GridItem, GridLine};
+    ///
+    /// let mut grid = grid!(header!("name"), header!("size")).unwrap();
+    /// add_line!(grid, "web-1", 10);
+    ///
+    /// let headers = grid.headers();
+    /// let line = GridLine::from(vec![
+    ///     GridItem::new(headers[0].clone(), "web-2".to_string()),
+    ///     GridItem::new(headers[1].clone(), "20".to_string()),
+    /// ]);
+    /// let old = grid.replace_line(0, line.clone()).unwrap();
+    /// assert_eq!(old.items()[0].contents(), "web-1");
+    /// assert_eq!(grid.display().unwrap(), "name        size\n----------------\nweb-2       20\n");
+    ///
+    /// let err = grid.replace_line(1, line).unwrap_err();
+    /// assert_eq!(err.to_string(), "no line at 1");
+    /// ```
+    pub fn replace_line(&mut self, idx: usize, line: GridLine) -> Result<GridLine> {
+        match self.lines.get_mut(idx) {
+            Some(existing) => {
+                let line = std::mem::replace(existing, line.transformed());
+                self.memory_used = (self.memory_used + self.lines[idx].memory_size())
+                    .saturating_sub(line.memory_size());
+                Ok(line)
+            }
+            None => Err(anyhow!("no line at {}", idx)),
+        }
+    }
+
+    /// Yield the lines of the grid by their positions. Sorting only changes the order the lines
+    /// are displayed in, not their positions.
+    pub fn lines(&self) -> &[GridLine] {
+        &self.lines
+    }
+
+    pub fn clear_lines(&mut self) {
+        self.lines.clear();
+        self.order.clear();
+        self.memory_used = 0;
+        self.skipped = 0;
+        self.skip_marker = None;
+    }
+
+    pub fn headers(&self) -> HeaderList {
+        self.headers.clone()
+    }
+
+    /// Yield the names of the columns, which are the keys of their headers (see
+    /// [GridHeader::key]), in order. This is useful for shell completion of a `--columns` flag.
+    pub fn column_names(&self) -> Vec<String> {
+        self.headers
+            .iter()
+            .map(|h| h.borrow().key().to_string())
+            .collect()
+    }
+
+    /// Yield the names of the columns which start with the prefix; see [TTYGrid::column_names].
+    ///
+    /// ```
+    /// use ttygrid::{grid, header};
+    ///
+    /// let grid = grid!(header!("name"), header!("namespace"), header!("size")).unwrap();
+    /// assert_eq!(grid.columns_matching("na"), vec!["name", "namespace"]);
+    /// assert!(grid.columns_matching("x").is_empty());
+    /// ```
+    pub fn columns_matching(&self, prefix: &str) -> Vec<String> {
+        self.column_names()
+            .into_iter()
+            .filter(|name| name.starts_with(prefix))
+            .collect()
+    }
+
+    // the position of the column with the key; see TTYGrid::column_names.
+    fn column_index(&self, name: &str) -> Result<usize> {
+        self.headers
+            .iter()
+            .position(|h| h.borrow().key() == name)
+            .ok_or_else(|| anyhow!("no column named {:?}", name))
+    }
+
+    fn header_index(&self, header: &SafeGridHeader) -> Result<usize> {
+        self.headers
+            .0
+            .iter()
+            .position(|h| Rc::ptr_eq(h, header))
+            .ok_or_else(|| {
+                anyhow!(
+                    "header '{}' is not a part of this grid",
+                    header.borrow().text
+                )
+            })
+    }
+
+    /// Replace values in the header's column that repeat the value of the line directly above it,
+    /// like the grouping in `ps` output. This is best used on sorted tables. Column widths are
+    /// still calculated from the original values.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, assert_grid_eq, grid, header, Dedup};
+    ///
+    /// let user = header!("user");
+    /// let mut grid = grid!(user.clone(), header!("pid")).unwrap();
+    /// add_line!(grid, "root", 1);
+    /// add_line!(grid, "root", 2);
+    /// add_line!(grid, "www", 3);
+    /// add_line!(grid, "www", 4);
+    ///
+    /// grid.dedup_display(user, Dedup::Ditto).unwrap();
+    /// assert_grid_eq!(
+    ///     grid,
+    ///     "
+    ///     user       pid
+    ///     --------------
+    ///     root       1
+    ///     \"          2
+    ///     www        3
+    ///     \"          4
+    ///     "
+    /// );
+    /// ```
+    pub fn dedup_display(&mut self, header: SafeGridHeader, style: Dedup) -> Result<()> {
+        let idx = self.header_index(&header)?;
+        self.dedup.retain(|(i, _)| *i != idx);
+        self.dedup.push((idx, style));
+        Ok(())
+    }
+
+    /// Select the header for display at the position within the selected headers; positions past
+    /// the end select it last. The selection is kept by the grid, so the header itself is not
+    /// changed and may be shared with other grids.
+    pub fn select(&mut self, header: SafeGridHeader, idx: usize) {
+        let idx = idx.min(self.selected.len());
+        self.selected.0.insert(idx, header)
+    }
+
+    /// Yield the headers selected by the last layout, in the order they are displayed.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header};
+    ///
+    /// let (name, size) = (header!("name"), header!("size"));
+    /// let mut grid = grid!(name.clone(), size.clone()).unwrap();
+    /// add_line!(grid, "a", 10);
+    ///
+    /// grid.deselect_all_headers();
+    /// grid.select(name.clone(), 0);
+    /// grid.select(size.clone(), 0);
+    ///
+    /// let selected = grid.selected_headers();
+    /// assert!(std::rc::Rc::ptr_eq(&selected[0], &size));
+    /// assert!(std::rc::Rc::ptr_eq(&selected[1], &name));
+    /// ```
+    pub fn selected_headers(&self) -> Vec<SafeGridHeader> {
+        self.selected.0.clone()
+    }
+
+    pub fn is_selected(&self, header: SafeGridHeader) -> bool {
+        self.selected.iter().any(|h| Rc::ptr_eq(h, &header))
+    }
+
+    pub fn select_all_headers(&mut self) {
+        self.selected = self.headers.clone()
+    }
+
+    pub fn deselect_all_headers(&mut self) {
+        self.selected.0.clear()
+    }
+
+    fn set_grid_max_len(&mut self, len_map: &LengthMapper) -> Result<()> {
+        let mut cached_columns = Vec::new();
+        let mut rounding = Vec::new();
+
+        for (idx, header) in self.headers.0.iter_mut().enumerate() {
+            let max_len = len_map.max_len_for_column(&header.borrow())?;
+            let unrounded = len_map.unrounded_len_for_column(&header.borrow())?;
+            if max_len > unrounded {
+                rounding.push((idx, max_len - unrounded));
+            }
+
+            header.borrow_mut().set_max_len(max_len);
+            cached_columns.insert(idx, header.borrow().max_len);
+        }
+        self.rounding = rounding;
+
+        let (headers, suffixes) = (&self.headers, &self.suffixes);
+        for line in self.lines.iter_mut() {
+            for (idx, item) in line.items.iter_mut().enumerate() {
+                if let Some(column) = cached_columns.get(idx) {
+                    item.set_max_len(column.unwrap());
+                }
+
+                item.suffix_len = suffixes
+                    .iter()
+                    .find(|(idx, _)| Rc::ptr_eq(&headers.0[*idx], &item.header))
+                    .map(|(_, suffix)| *suffix)
+                    .unwrap_or_default();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn measure(&mut self) -> Result<LengthMapper> {
+        let mut len_map = LengthMapper {
+            quantum: self.width_quantum,
+            width: self.width,
+            ..Default::default()
+        };
+        let tree = self.tree_prefixes();
+        let first = self.candidate_headers().0.first().cloned();
+        let indent = self.indent.chars().count();
+
+        let indices = self
+            .sample
+            .indices(self.lines.len())
+            .filter(|idx| self.line_shown(*idx))
+            .collect::<Vec<usize>>();
+        let lens = item_lengths(&self.lines, &indices);
+
+        self.suffixes = self.measure_suffixes(&indices);
+
+        for (i, mut lens) in indices.into_iter().zip(lens) {
+            if let (Some(tree), Some(tree_column)) = (&tree, self.tree_column) {
+                if let Some(len) = lens.get_mut(tree_column) {
+                    *len += tree[i].chars().count();
+                }
+            }
+
+            // the indentation is measured in the first column which may be displayed
+            if let (Some(first), true) = (&first, self.lines[i].indent > 0) {
+                let mut items = self.lines[i].items.iter();
+                if let Some(pos) = items.position(|item| Rc::ptr_eq(&item.header, first)) {
+                    lens[pos] += indent * self.lines[i].indent;
+                }
+            }
+
+            if !self.redactions.is_empty() {
+                for (item, len) in self.lines[i].items.iter().zip(lens.iter_mut()) {
+                    let redacted = self.redacted(&item.header, &item.contents);
+                    if let Cow::Owned(redacted) = redacted {
+                        *len = content_len(&redacted);
+                    }
+                }
+            }
+
+            if self.wrap {
+                for (item, len) in self.lines[i].items.iter().zip(lens.iter_mut()) {
+                    if let Some(max_width) = item.header.borrow().max_width {
+                        *len = (*len).min(max_width.max(1) + 1);
+                    }
+                }
+            }
+
+            if !self.suffixes.is_empty() {
+                for (item, len) in self.lines[i].items.iter().zip(lens.iter_mut()) {
+                    let alignment = item.header.borrow().alignment;
+                    *len += self
+                        .column_suffix(&item.header)
+                        .saturating_sub(suffix_len(&item.contents, alignment));
+                }
+            }
+
+            len_map.map_line(&self.lines[i], &lens);
+        }
+
+        // labels are not measured, but the sort indicator is reserved so it does not take the
+        // place of the label.
+        for header in self.headers.iter() {
+            len_map.reserve(header, self.sort_mark(header).chars().count());
+        }
+
+        self.set_grid_max_len(&len_map)?;
+        Ok(len_map)
+    }
+
+    // finds the longest suffix of each column aligned on a decimal point or a unit, by the
+    // position of its header.
+    fn measure_suffixes(&self, indices: &[usize]) -> Vec<(usize, usize)> {
+        let mut ret = Vec::new();
+
+        for (idx, header) in self.headers.iter().enumerate() {
+            let alignment = header.borrow().alignment;
+            if !matches!(alignment, Alignment::Decimal | Alignment::Unit) {
+                continue;
+            }
+
+            let suffix = indices
+                .iter()
+                .flat_map(|i| self.lines[*i].items.iter())
+                .filter(|item| Rc::ptr_eq(&item.header, header))
+                .map(|item| suffix_len(&item.contents, alignment))
+                .max()
+                .unwrap_or_default();
+
+            ret.push((idx, suffix));
+        }
+
+        ret
+    }
+
+    // the longest suffix of the header's column, as last measured.
+    fn column_suffix(&self, header: &SafeGridHeader) -> usize {
+        self.suffixes
+            .iter()
+            .find(|(idx, _)| Rc::ptr_eq(&self.headers.0[*idx], header))
+            .map(|(_, suffix)| *suffix)
+            .unwrap_or_default()
+    }
+
+    // the narrowest layout which is acceptable: all the essential columns, or if there are none,
+    // the highest priority column.
+    fn narrowest_layout(&self) -> Vec<usize> {
+        let essential = self.essential();
+
+        if !essential.is_empty() {
+            return essential;
+        }
+
+        self.candidates()
+            .into_iter()
+            .filter(|idx| self.marker_column(*idx).is_none() && self.icon_column(*idx).is_none())
+            .rev() // max_by_key yields the last maximum; prefer the leftmost column
+            .max_by_key(|idx| self.layout_priority(*idx))
+            .map(|idx| vec![idx])
+            .unwrap_or_default()
+    }
+
+    fn essential(&self) -> Vec<usize> {
+        self.candidates()
+            .into_iter()
+            .filter(|idx| self.headers.0[*idx].borrow().is_essential())
+            .collect()
+    }
+
+    // the headers which may be displayed: those in the active view, or all of them.
+    fn candidates(&self) -> Vec<usize> {
+        let candidates = match self.view() {
+            Some(view) => view.to_vec(),
+            None => (0..self.headers.len()).collect(),
+        };
+
+        let mut scrolled = 0;
+        candidates
+            .into_iter()
+            .filter(|idx| !self.hidden_index(*idx) && !self.hidden_uninformative(*idx))
+            .filter(|idx| {
+                if self.frozen.contains(idx) || scrolled >= self.column_offset {
+                    true
+                } else {
+                    scrolled += 1;
+                    false
+                }
+            })
+            .collect()
+    }
+
+    /// Hide or show the header's column. Hidden columns are never displayed, regardless of the
+    /// terminal width.
+    pub fn set_hidden(&mut self, header: SafeGridHeader, hidden: bool) -> Result<()> {
+        let idx = self.header_index(&header)?;
+        self.set_hidden_index(idx, hidden);
+        Ok(())
+    }
+
+    fn set_hidden_index(&mut self, idx: usize, hidden: bool) {
+        self.hidden.retain(|i| *i != idx);
+        self.shown.retain(|i| *i != idx);
+        if hidden {
+            self.hidden.push(idx)
+        } else {
+            self.shown.push(idx)
+        }
+    }
+
+    /// Whether the header's column is hidden, either with [TTYGrid::set_hidden] or because it is
+    /// hidden by default (see [GridHeader::set_default_hidden]) and has not been shown.
+    pub fn is_hidden(&self, header: SafeGridHeader) -> bool {
+        self.header_index(&header)
+            .map(|idx| self.hidden_index(idx))
+            .unwrap_or_default()
+    }
+
+    fn hidden_index(&self, idx: usize) -> bool {
+        if self.hidden.contains(&idx) {
+            return true;
+        }
+
+        // columns hidden by default are shown when they are explicitly asked for
+        self.headers.0[idx].borrow().default_hidden
+            && !self.shown.contains(&idx)
+            && !self.view().is_some_and(|view| view.contains(&idx))
+    }
+
+    // the columns of the active view, if there is one.
+    fn view(&self) -> Option<&[usize]> {
+        self.active_view
+            .as_ref()
+            .and_then(|name| self.views.iter().find(|(n, _)| n == name))
+            .map(|(_, view)| view.as_slice())
+    }
+
+    /// Freeze or unfreeze the header's column. Frozen columns stay in place when the [Pager] is
+    /// scrolled horizontally, which is useful for key columns such as names. They are also
+    /// displayed when the terminal is too narrow for every column, regardless of their priority.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, test::FakeTerm, Priority};
+    ///
+    /// let name = header!("name", Priority::Debug);
+    /// let mut grid = grid!(name.clone(), header!("status"), header!("address")).unwrap();
+    /// add_line!(grid, "web-1", "running", "10.0.0.4");
+    /// grid.refresh_width(&FakeTerm::new(30, 10)).unwrap();
+    /// assert_eq!(
+    ///     grid.display().unwrap(),
+    ///     "status        address\n----------------------\nrunning       10.0.0.4\n"
+    /// );
+    ///
+    /// grid.set_frozen(name.clone(), true).unwrap();
+    /// assert!(grid.is_frozen(name));
+    /// assert_eq!(
+    ///     grid.display().unwrap(),
+    ///     "name        address\n--------------------\nweb-1       10.0.0.4\n"
+    /// );
+    /// ```
+    pub fn set_frozen(&mut self, header: SafeGridHeader, frozen: bool) -> Result<()> {
+        let idx = self.header_index(&header)?;
+        self.frozen.retain(|i| *i != idx);
+        if frozen {
+            self.frozen.push(idx)
+        }
+        Ok(())
+    }
+
+    pub fn is_frozen(&self, header: SafeGridHeader) -> bool {
+        self.header_index(&header)
+            .map(|idx| self.frozen.contains(&idx))
+            .unwrap_or_default()
+    }
+
+    /// Yield a [Pager] for browsing this grid interactively.
+    pub fn pager(&mut self) -> Pager<'_> {
+        Pager::new(self)
+    }
+
+    /// Display the grid on the alternate screen of the terminal, much like a man page, and
+    /// restore the terminal when the user is done. Tables which are too long for the screen can be
+    /// scrolled; see [Pager] for the keys. If the table fits on the screen, any key leaves.
+    pub fn show_paged(&mut self) -> Result<()> {
+        let mut pager = self.pager();
+        pager.set_quit_on_any_key(true);
+        pager.run(std::io::stdout())
+    }
+
+    fn candidate_headers(&self) -> HeaderList {
+        self.candidates()
+            .into_iter()
+            .map(|idx| self.headers.0[idx].clone())
+            .collect()
+    }
+
+    /// Define a named view, which is a curated set of columns, such as a "wide" and a "compact"
+    /// view. Columns within the view are still dropped if the terminal is too small to display
+    /// them, and are displayed in the order the grid defines them. See
+    /// [TTYGrid::set_active_view].
+    ///
+    /// ```
+    /// use ttygrid::{add_line, assert_grid_eq, grid, header};
+    ///
+    /// let name = header!("name");
+    /// let ip = header!("ip");
+    /// let node = header!("node");
+    /// let mut grid = grid!(name.clone(), header!("status"), ip.clone(), node.clone()).unwrap();
+    /// add_line!(grid, "web-1", "running", "10.0.0.4", "node-a");
+    /// grid.define_view("compact", [name.clone()]).unwrap();
+    /// grid.define_view("wide", [node, name, ip]).unwrap();
+    ///
+    /// grid.set_active_view("wide").unwrap();
+    /// assert_grid_eq!(
+    ///     grid,
+    ///     "
+    ///     name        ip             node
+    ///     ---------------------------------
+    ///     web-1       10.0.0.4       node-a
+    ///     "
+    /// );
+    ///
+    /// grid.set_active_view("compact").unwrap();
+    /// assert_eq!(grid.display().unwrap(), "name\n-----\nweb-1\n");
+    /// assert!(grid.set_active_view("huge").is_err());
+    /// ```
+    pub fn define_view(
+        &mut self,
+        name: &str,
+        headers: impl IntoIterator<Item = SafeGridHeader>,
+    ) -> Result<()> {
+        let mut view = headers
+            .into_iter()
+            .map(|h| self.header_index(&h))
+            .collect::<Result<Vec<usize>>>()?;
+        view.sort_unstable();
+        view.dedup();
+
+        self.views.retain(|(n, _)| n != name);
+        self.views.push((name.to_string(), view));
+        Ok(())
+    }
+
+    /// Display only the columns of the view defined with [TTYGrid::define_view].
+    pub fn set_active_view(&mut self, name: &str) -> Result<()> {
+        if !self.views.iter().any(|(n, _)| n == name) {
+            return Err(anyhow!("no view named '{}'", name));
+        }
+
+        self.active_view = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Go back to considering all columns for display.
+    pub fn clear_active_view(&mut self) {
+        self.active_view = None
+    }
+
+    pub fn active_view(&self) -> Option<&str> {
+        self.active_view.as_deref()
+    }
+
+    /// Yield the minimum terminal width needed to display this grid's current content: the width
+    /// of all the [Priority::Essential] columns, or, if there are none, the width of the highest
+    /// priority column.
+    pub fn minimum_width(&mut self) -> Result<usize> {
+        let mut len_map = self.measure()?;
+        let layout = self.narrowest_layout();
+        let headers = layout
+            .iter()
+            .map(|idx| self.headers.0[*idx].clone())
+            .collect::<HeaderList>();
+
+        len_map.max_len_for_headers(headers)
+    }
+
+    fn terminal_too_small(&mut self) -> Result<TerminalTooSmall> {
+        let minimum_width = self.minimum_width()?;
+        let narrowest_layout = self
+            .narrowest_layout()
+            .into_iter()
+            .map(|idx| self.header_id(idx))
+            .collect();
+        let essential = self
+            .essential()
+            .into_iter()
+            .map(|idx| self.header_id(idx))
+            .collect();
+
+        Ok(TerminalTooSmall {
+            width: self.width,
+            minimum_width,
+            narrowest_layout,
+            essential,
+        })
+    }
+
+    fn determine_headers(&mut self) -> Result<()> {
+        // lazy items are measured as empty until their column is selected; the layout is redone
+        // once the selected ones have their contents.
+        self.apply_sort();
+
+        loop {
+            self.layout_headers()?;
+
+            let mut shown = self.selected.clone();
+            for table in self.overflow_tables.iter() {
+                shown.0.extend(table.iter().cloned());
+            }
+
+            if !self.materialize(&shown) {
+                self.notify_layout_computed();
+                return Ok(());
+            }
+        }
+    }
+
+    // produces the contents of the lazy items of the headers, yielding whether there were any.
+    fn materialize(&mut self, headers: &HeaderList) -> bool {
+        let mut found = false;
+
+        for item in self.lines.iter_mut().flat_map(|line| line.items.iter_mut()) {
+            if item.lazy.is_some() && headers.0.contains(&item.header) {
+                item.materialize();
+                found = true;
+            }
+        }
+
+        found
+    }
+
+    fn layout_headers(&mut self) -> Result<()> {
+        self.select_headers()?;
+        self.apply_pins();
+        self.apply_weights();
+        self.apply_flush_right();
+        Ok(())
+    }
+
+    // widens the column before the flush right column by the space the table leaves, so the
+    // table ends at the edge of the terminal; see set_flush_right.
+    fn apply_flush_right(&mut self) {
+        let pos = match self.flush_right.and_then(|idx| {
+            let header = &self.headers.0[idx];
+            self.selected.iter().position(|h| Rc::ptr_eq(h, header))
+        }) {
+            Some(pos) if pos > 0 => pos,
+            _ => return,
+        };
+
+        // trimmed lines end where the contents of the last column do, before its gap
+        let mut reach = self.width;
+        if self.trim_trailing_whitespace {
+            reach += self
+                .selected
+                .0
+                .last()
+                .map(|h| h.borrow().gap())
+                .unwrap_or_default();
+        }
+        let spare = reach.saturating_sub(self.total_width());
+        if spare == 0 {
+            return;
+        }
+
+        let gutter = self.selected.0[pos - 1].clone();
+        let max_len = gutter.borrow().max_len.unwrap_or_default() + spare;
+        gutter.borrow_mut().set_max_len(max_len);
+
+        for line in self.lines.iter_mut() {
+            let item = line
+                .items
+                .iter_mut()
+                .find(|i| Rc::ptr_eq(&i.header, &gutter));
+            if let Some(item) = item {
+                item.set_max_len(max_len);
+            }
+        }
+    }
+
+    // moves the pinned columns of each table to their edges, keeping the order of the rest.
+    fn apply_pins(&mut self) {
+        let rank = |header: &SafeGridHeader| match header.borrow().pin {
+            Some(Pin::Left) => 0,
+            None => 1,
+            Some(Pin::Right) => 2,
+        };
+
+        self.selected.0.sort_by_key(rank);
+        for table in self.overflow_tables.iter_mut() {
+            table.0.sort_by_key(rank);
+        }
+    }
+
+    // distributes the width left over by the selected headers to the weighted ones.
+    fn apply_weights(&mut self) {
+        let weight = |header: &SafeGridHeader| match header.borrow().width {
+            Some(Width::Weight(weight)) => weight,
+            _ => 0,
+        };
+
+        let total = self.selected.iter().map(weight).sum::<usize>();
+        if total == 0 {
+            return;
+        }
+
+        let used = self
+            .selected
+            .iter()
+            .map(|h| h.borrow().max_len.unwrap_or_default())
+            .sum::<usize>();
+        let leftover = self.width.saturating_sub(used);
+
+        for header in self.headers.0.iter() {
+            if !self.selected.iter().any(|h| Rc::ptr_eq(h, header)) || weight(header) == 0 {
+                continue;
+            }
+
+            let max_len =
+                header.borrow().max_len.unwrap_or_default() + leftover * weight(header) / total;
+            header.borrow_mut().set_max_len(max_len);
+
+            for line in self.lines.iter_mut() {
+                let item = line
+                    .items
+                    .iter_mut()
+                    .find(|i| Rc::ptr_eq(&i.header, header));
+                if let Some(item) = item {
+                    item.set_max_len(max_len);
+                }
+            }
+        }
+    }
+
+    fn select_headers(&mut self) -> Result<()> {
+        self.overflow_tables.clear();
+        self.refresh_ages();
+        let mut len_map = self.measure()?; // this has to happen before any return occurs
+        let candidates = self.candidate_headers();
+        let last = len_map.max_len_for_headers(candidates.clone())?;
+
+        if last <= self.width {
+            self.selected = candidates;
+            return Ok(());
+        }
+
+        if candidates.iter().any(|h| self.is_frozen(h.clone())) {
+            self.select_around_frozen(&mut len_map, &candidates)?;
+        } else {
+            self.select_by_priority(&mut len_map, candidates.clone())?;
+        }
+
+        let missing = self
+            .essential()
+            .into_iter()
+            .any(|idx| !self.is_selected(self.headers.0[idx].clone()));
+
+        if missing || (self.selected.is_empty() && !candidates.is_empty()) {
+            return Err(self.terminal_too_small()?.into());
+        }
+
+        if let Some(callback) = &self.on_layout_degraded {
+            let dropped = self.dropped_headers();
+            if !dropped.is_empty() {
+                callback(&dropped)
+            }
+        }
+
+        if self.overflow == OverflowPolicy::Stack {
+            self.stack_overflow(&mut len_map)?;
+        }
+
+        Ok(())
+    }
+
+    // renders the age items as of now, before they are measured.
+    fn refresh_ages(&mut self) {
+        let now = std::time::SystemTime::now();
+        for item in self.lines.iter_mut().flat_map(|line| line.items.iter_mut()) {
+            if let Some(age) = item.age {
+                item.contents = age.render_at(now);
+            }
+        }
+    }
+
+    // lays out the columns which were dropped into additional tables, each starting with the key
+    // column. self.selected is left as the layout of the first table.
+    fn stack_overflow(&mut self, len_map: &mut LengthMapper) -> Result<()> {
+        let first = self.selected.clone();
+        let key = self.headers.0[self.key_column.min(self.headers.len() - 1)].clone();
+        let key_width = len_map.max_len_for_column(&key.borrow())?;
+        let mut shown = first.clone();
+
+        loop {
+            let remaining = self
+                .candidate_headers()
+                .into_iter()
+                .filter(|h| !shown.0.contains(h) && !Rc::ptr_eq(h, &key))
+                .collect::<HeaderList>();
+
+            if remaining.is_empty() {
+                break;
+            }
+
+            let width = self.width;
+            self.width = width.saturating_sub(key_width);
+            let fits = len_map.max_len_for_headers(remaining.clone())? <= self.width;
+            let res = if fits {
+                self.selected = remaining.clone();
+                Ok(())
+            } else {
+                self.select_by_priority(len_map, remaining.clone())
+            };
+            self.width = width;
+            res?;
+
+            // a column too wide to fit with the key column is displayed anyway
+            if self.selected.is_empty() {
+                self.selected.push(remaining[0].clone());
+            }
+
+            let mut table = HeaderList::new();
+            for header in self.headers.0.iter() {
+                if Rc::ptr_eq(header, &key) || self.selected.0.contains(header) {
+                    table.push(header.clone());
+                }
+            }
+
+            shown.0.extend(self.selected.0.iter().cloned());
+            self.overflow_tables.push(table);
+        }
+
+        self.selected = first;
+        Ok(())
+    }
+
+    // selects the frozen candidates regardless of their priority, and lays out the remaining
+    // candidates in the space left over.
+    fn select_around_frozen(
+        &mut self,
+        len_map: &mut LengthMapper,
+        candidates: &HeaderList,
+    ) -> Result<()> {
+        let (frozen, rest): (Vec<_>, Vec<_>) = candidates
+            .iter()
+            .cloned()
+            .partition(|h| self.is_frozen(h.clone()));
+        let (frozen, rest) = (HeaderList(frozen), HeaderList(rest));
+
+        let width = self.width;
+        self.width = width.saturating_sub(len_map.max_len_for_headers(frozen.clone())?);
+        let res = if rest.is_empty() {
+            self.deselect_all_headers();
+            Ok(())
+        } else if len_map.max_len_for_headers(rest.clone())? <= self.width {
+            self.selected = rest;
+            Ok(())
+        } else {
+            self.select_by_priority(len_map, rest)
+        };
+        self.width = width;
+        res?;
+
+        let selected = std::mem::take(&mut self.selected);
+        for (idx, header) in candidates
+            .iter()
+            .filter(|h| frozen.0.contains(h) || selected.0.contains(h))
+            .enumerate()
+        {
+            self.select(header.clone(), idx);
+        }
+
+        Ok(())
+    }
+
+    /// Set what happens to columns which do not fit in the terminal.
+    pub fn set_overflow_policy(&mut self, overflow: OverflowPolicy) {
+        self.overflow = overflow
+    }
+
+    /// Set the column which is repeated in every table when the overflow policy is
+    /// [OverflowPolicy::Stack]. The first column is used by default.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, test::FakeTerm, OverflowPolicy, Priority};
+    ///
+    /// let name = header!("name", Priority::Essential);
+    /// let mut grid = grid!(
+    ///     name.clone(),
+    ///     header!("status"),
+    ///     header!("address"),
+    ///     header!("node")
+    /// )
+    /// .unwrap();
+    /// add_line!(grid, "web-1", "running", "10.0.0.4", "node-a");
+    /// grid.set_overflow_policy(OverflowPolicy::Stack);
+    /// grid.set_key_column(name).unwrap();
+    /// grid.refresh_width(&FakeTerm::new(30, 10)).unwrap();
+    ///
+    /// let tables = grid.display().unwrap();
+    /// assert_eq!(tables.split("\n\n").count(), 3);
+    /// assert_eq!(
+    ///     tables,
+    ///     "name        address\n--------------------\nweb-1       10.0.0.4\n\n\
+    ///      name        status\n-------------------\nweb-1       running\n\n\
+    ///      name        node\n------------------\nweb-1       node-a\n"
+    /// );
+    /// ```
+    pub fn set_key_column(&mut self, header: SafeGridHeader) -> Result<()> {
+        self.key_column = self.header_index(&header)?;
+        Ok(())
+    }
+
+    // yields the layouts of every table to render: the layout of the grid, followed by any
+    // stacked overflow tables.
+    fn layouts(&self) -> Vec<HeaderList> {
+        let mut ret = vec![self.selected.clone()];
+        ret.extend(self.overflow_tables.iter().cloned());
+        ret
+    }
+
+    /// Set a function which yields the label to display for a header's key, which allows header
+    /// labels to be localized at render time. If the function yields [None], the header's text is
+    /// displayed.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header};
+    ///
+    /// let mut grid = grid!(header!("name", key = "name"), header!("size")).unwrap();
+    /// add_line!(grid, "web-1", 10);
+    /// grid.set_label_provider(|key| match key {
+    ///     "name" => Some("nom".to_string()),
+    ///     _ => None,
+    /// });
+    /// assert_eq!(
+    ///     grid.display().unwrap(),
+    ///     "nom         size\n----------------\nweb-1       10\n"
+    /// );
+    /// ```
+    pub fn set_label_provider(&mut self, provider: impl Fn(&str) -> Option<String> + 'static) {
+        self.label_provider = Some(Rc::new(provider))
+    }
+
+    fn label(&self, header: &GridHeader) -> String {
+        self.label_provider
+            .as_ref()
+            .and_then(|provider| provider(header.key()))
+            .unwrap_or_else(|| header.text.to_string())
+    }
+
+    fn header_line(&self) -> String {
+        self.header_line_of(&self.selected)
+    }
+
+    fn header_line_of(&self, selected: &HeaderList) -> String {
+        let mut ret = String::new();
+        let ellipsis = self
+            .label_ellipsis
+            .as_deref()
+            .unwrap_or_else(|| self.charset.ellipsis());
+        let mut used = 0;
+
+        for (idx, header) in selected.0.iter().enumerate() {
+            let mark = self.sort_mark(header);
+            let header = header.borrow();
+            let last = idx == selected.len() - 1;
+            let mut label = self.header_decoration.apply(&self.label(&header));
+
+            // labels are not measured; those longer than their column are shortened, leaving a
+            // space before the next column. The last one may take the rest of the terminal.
+            let width = match (header.content_width(), header.max_len) {
+                (Some(width), _) => Some(width),
+                (None, Some(max_len)) if last => Some(self.width.saturating_sub(used).max(max_len)),
+                (None, Some(max_len)) => Some(max_len.saturating_sub(1)),
+                (None, None) => None,
+            };
+            // the sort indicator is kept when the label is shortened
+            if let Some(width) = width {
+                let width = width.saturating_sub(mark.chars().count());
+                label = clip_with(&label, width, ellipsis);
+            }
+            label += &mark;
+            used += header.max_len.unwrap_or_default();
+
+            let aligned = align(
+                &label,
+                header.max_len.unwrap_or(label.chars().count() + 2),
+                header.gap(),
+                header.alignment,
+            );
+
+            if self.trim_trailing_whitespace && last {
+                ret += aligned.trim_end();
+            } else {
+                ret += &aligned;
+            }
+        }
+
+        ret
+    }
+
+    /// Set the text header labels end with when they are longer than their columns and are
+    /// shortened, so the columns stay aligned. Labels are not measured when the columns are laid
+    /// out, so a label may be longer than the items of its column. The ellipsis of the
+    /// [Charset] is used by default.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header};
+    ///
+    /// let mut grid = grid!(header!("priority"), header!("name")).unwrap();
+    /// add_line!(grid, 1, "web");
+    /// grid.set_label_ellipsis("~");
+    ///
+    /// let output = grid.display().unwrap();
+    /// assert!(output.starts_with("priori~ name"));
+    /// assert_eq!(output.lines().nth(2).unwrap().find("web"), Some(8));
+    /// ```
+    pub fn set_label_ellipsis(&mut self, ellipsis: impl Into<String>) {
+        self.label_ellipsis = Some(ellipsis.into())
+    }
+
+    /// Change the case of the header labels when they are displayed, and underline them in
+    /// terminal output ([TTYGrid::write] and the [Pager]), so header presentation can be
+    /// standardized across an application. The headers themselves, and their labels in exports,
+    /// are not changed.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, Decoration};
+    ///
+    /// let mut grid = grid!(header!("name"), header!("disk_usage")).unwrap();
+    /// add_line!(grid, "a", 10);
+    ///
+    /// grid.set_header_decoration(Decoration::Title, false);
+    /// assert!(grid.display().unwrap().starts_with("Name    Disk_Usage"));
+    ///
+    /// grid.set_header_decoration(Decoration::Upper, true);
+    /// assert!(grid.display().unwrap().starts_with("NAME    DISK_USAGE"));
+    /// ```
+    pub fn set_header_decoration(&mut self, decoration: Decoration, underline: bool) {
+        self.header_decoration = decoration;
+        self.underline_header = underline;
+    }
+
+    /// Set the string lines are indented with for each level; see [GridLine::set_indent]. The
+    /// default is two spaces.
+    pub fn set_indent_string(&mut self, indent: impl Into<String>) {
+        self.indent = indent.into()
+    }
+
+    /// Select the characters used for tree guides, sparklines and other decorations. Unicode is
+    /// the default.
+    pub fn set_charset(&mut self, charset: Charset) {
+        self.charset = charset
+    }
+
+    pub fn charset(&self) -> Charset {
+        self.charset
+    }
+
+    /// Wrap items which contain newlines onto several lines of the terminal, keeping each line
+    /// within its column. Columns are as wide as the longest line of their items. This is off by
+    /// default.
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap
+    }
+
+    /// Limit each row to n lines of the terminal when wrapping; see [TTYGrid::set_wrap]. Items
+    /// which are clipped end with an ellipsis, which takes the place of their last characters;
+    /// see [Charset]. The [Pager] can expand clipped rows back to their full height.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, Charset};
+    ///
+    /// let mut grid = grid!(header!("notes", max = 10), header!("size")).unwrap();
+    /// add_line!(grid, "restarting twice", 10);
+    /// grid.set_wrap(true);
+    /// grid.set_max_row_height(Some(1));
+    ///
+    /// let output = grid.display().unwrap();
+    /// assert_eq!(output.lines().nth(2), Some("restartin…       10"));
+    ///
+    /// grid.set_charset(Charset::Ascii);
+    /// let output = grid.display().unwrap();
+    /// assert_eq!(output.lines().nth(2), Some("restart...       10"));
+    /// ```
+    pub fn set_max_row_height(&mut self, n: Option<usize>) {
+        self.max_row_height = n
+    }
+
+    /// Select how words which are longer than the maximum width of their column are broken when
+    /// wrapping; see [GridHeader::set_max_width]. Words are broken anywhere by default.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, WordBreak};
+    ///
+    /// let mut grid = grid!(header!("name", max = 8), header!("size")).unwrap();
+    /// add_line!(grid, "the superlongword fox", "10");
+    /// grid.set_wrap(true);
+    /// grid.set_word_break(WordBreak::Hyphenate);
+    ///
+    /// let output = grid.display().unwrap();
+    /// let lines = output.lines().skip(2).map(str::trim_end).collect::<Vec<&str>>();
+    /// assert_eq!(lines, vec!["the            10", "superlo-", "ngword", "fox"]);
+    /// ```
+    pub fn set_word_break(&mut self, word_break: WordBreak) {
+        self.word_break = word_break
+    }
+
+    // splits the contents of the item into the lines it is displayed on.
+    fn wrap_item(&self, item: &GridItem) -> Vec<String> {
+        if item.raw.is_some() {
+            return vec![item.contents.clone()];
+        }
+
+        let header = item.header.borrow();
+        let max_width = match (header.max_width, header.content_width()) {
+            (Some(max_width), Some(width)) => Some(max_width.min(width)),
+            (max_width, width) => max_width.or(width),
+        };
+        drop(header);
+
+        item.contents
+            .split('\n')
+            .flat_map(|line| match max_width {
+                Some(width) if line.chars().count() > width => {
+                    wrap_text(line, width.max(1), self.word_break)
+                }
+                _ => vec![line.to_string()],
+            })
+            .collect()
+    }
+
+    // splits a line into the lines of the terminal it is displayed on.
+    fn physical_lines(&self, line: &GridLine) -> Vec<GridLine> {
+        if !self.wrap {
+            return vec![line.clone()];
+        }
+
+        let wrapped = line
+            .items
+            .iter()
+            .map(|item| self.wrap_item(item))
+            .collect::<Vec<Vec<String>>>();
+
+        let height = wrapped.iter().map(|w| w.len()).max().unwrap_or(1);
+        let limit = match self.max_row_height {
+            Some(n) if !self.expand_rows => height.min(n.max(1)),
+            _ => height,
+        };
+
+        (0..limit)
+            .map(|row| {
+                let mut items = line
+                    .items
+                    .iter()
+                    .zip(wrapped.iter())
+                    .map(|(item, lines)| {
+                        let mut item = item.clone();
+                        item.contents = lines.get(row).cloned().unwrap_or_default();
+                        if row > 0 {
+                            item.raw = None;
+                        }
+                        // the ellipsis takes the place of the last characters which fit
+                        if row + 1 == limit && lines.len() > limit {
+                            let ellipsis = self.charset.ellipsis();
+                            let header = item.header.borrow();
+                            let room = header.max_len.map(|len| len.saturating_sub(header.gap()));
+                            drop(header);
+
+                            let keep = room.map_or(usize::MAX, |room| {
+                                room.saturating_sub(ellipsis.chars().count())
+                            });
+                            item.contents =
+                                item.contents.chars().take(keep).collect::<String>() + ellipsis;
+                        }
+                        item
+                    })
+                    .collect::<Vec<GridItem>>();
+
+                // continuation lines would otherwise end in the padding of the empty items
+                if self.trim_trailing_whitespace && row > 0 {
+                    while items.len() > 1 && items.last().is_some_and(|i| i.contents.is_empty()) {
+                        items.pop();
+                    }
+
+                    if let Some(item) = items.last_mut() {
+                        item.trim = true;
+                    }
+                }
+
+                GridLine {
+                    items,
+                    depth: line.depth,
+                    colors: line.colors,
+                    hidden: line.hidden,
+                    indent: line.indent,
+                    errors: Vec::new(),
+                    placeholder: line.placeholder,
+                }
+            })
+            .collect()
+    }
+
+    /// Skip padding the last column, so lines do not end in whitespace, which is noisy when the
+    /// output is saved to a file or compared. This is on by default.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header};
+    ///
+    /// let mut grid = grid!(header!("name"), header!("size")).unwrap();
+    /// add_line!(grid, "web-1", 10);
+    /// add_line!(grid, "web-2", 200);
+    /// let output = grid.display().unwrap();
+    /// assert!(output.ends_with("web-1       10\nweb-2       200\n"));
+    ///
+    /// grid.set_trim_trailing_whitespace(false);
+    /// let output = grid.display().unwrap();
+    /// assert!(output.ends_with("web-1       10        \nweb-2       200       \n"));
+    /// ```
+    pub fn set_trim_trailing_whitespace(&mut self, trim: bool) {
+        self.trim_trailing_whitespace = trim
+    }
+
+    /// Take over rendering of each line. The renderer is given the line with only the selected
+    /// items, the width of each selected column, and the writer; it must write the entire line
+    /// except for the trailing newline. Width negotiation and row colors are still handled by the
+    /// grid.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header};
+    ///
+    /// let mut grid = grid!(header!("name"), header!("size")).unwrap();
+    /// add_line!(grid, "web-1", 10);
+    /// add_line!(grid, "web-2", 200);
+    /// grid.set_row_renderer(|line, widths, writer| {
+    ///     let cells: Vec<String> = line
+    ///         .items()
+    ///         .iter()
+    ///         .zip(widths)
+    ///         .map(|(item, width)| format!("{:>1$}", item.contents(), width))
+    ///         .collect();
+    ///     write!(writer, "{}", cells.join("|"))?;
+    ///     Ok(())
+    /// });
+    ///
+    /// // the columns keep the widths the grid negotiated for the header
+    /// assert_eq!(
+    ///     grid.display().unwrap(),
+    ///     "name        size\n----------------\n       web-1|        10\n       web-2|       200\n"
+    /// );
+    /// ```
+    pub fn set_row_renderer(
+        &mut self,
+        renderer: impl Fn(&GridLine, &[usize], &mut dyn std::io::Write) -> Result<()> + 'static,
+    ) {
+        self.row_renderer = Some(Rc::new(renderer))
+    }
+
+    fn selected_widths(&self) -> Vec<usize> {
+        self.widths_of(&self.selected)
+    }
+
+    fn widths_of(&self, selected: &HeaderList) -> Vec<usize> {
+        selected
+            .0
+            .iter()
+            .map(|h| h.borrow().max_len.unwrap_or_default())
+            .collect()
+    }
+
+    /// Display the header's column as a tree, using the depth of each line (see
+    /// [GridLine::set_depth]) to draw indentation guides before the value, such as for process
+    /// trees or file hierarchies. Lines are expected to be in tree order, with children directly
+    /// after their parent.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, assert_grid_eq, grid, header};
+    ///
+    /// let mut grid = grid!(header!("name"), header!("size")).unwrap();
+    /// add_line!(grid, "init", 1);
+    /// add_line!(grid, "sshd", 20);
+    /// add_line!(grid, "bash", 300);
+    /// add_line!(grid, "cron", 4);
+    /// grid.set_tree_column(grid.headers()[0].clone()).unwrap();
+    /// grid.set_line_depth(1, 1).unwrap();
+    /// grid.set_line_depth(2, 2).unwrap();
+    /// grid.set_line_depth(3, 1).unwrap();
+    /// assert!(grid.set_line_depth(4, 1).is_err());
+    ///
+    /// assert_grid_eq!(
+    ///     grid,
+    ///     "
+    ///     name             size
+    ///     ---------------------
+    ///     init             1
+    ///     ├─ sshd          20
+    ///     │  └─ bash       300
+    ///     └─ cron          4
+    ///     "
+    /// );
+    /// ```
+    pub fn set_tree_column(&mut self, header: SafeGridHeader) -> Result<()> {
+        self.tree_column = Some(self.header_index(&header)?);
+        Ok(())
+    }
+
+    /// Set the depth of the line at the position; see [GridLine::set_depth].
+    pub fn set_line_depth(&mut self, idx: usize, depth: usize) -> Result<()> {
+        match self.lines.get_mut(idx) {
+            Some(line) => {
+                line.set_depth(depth);
+                Ok(())
+            }
+            None => Err(anyhow!("no line at {}", idx)),
+        }
+    }
+
+    // yields the indentation guides for every line when there is a tree column. This walks the
+    // lines backwards, tracking for each depth whether a sibling follows the current line.
+    fn tree_prefixes(&self) -> Option<Vec<String>> {
+        self.tree_column?;
+
+        let mut ret = vec![String::new(); self.lines.len()];
+        let mut has_next: Vec<bool> = Vec::new();
+
+        for (idx, line) in self.lines.iter().enumerate().rev() {
+            if !self.line_shown(idx) {
+                continue;
+            }
+
+            let depth = line.depth;
+            if has_next.len() <= depth {
+                has_next.resize(depth + 1, false);
+            }
+
+            let mut prefix = String::new();
+            for (level, next) in has_next.iter().enumerate().take(depth + 1).skip(1) {
+                prefix += match (level == depth, next) {
+                    (false, true) => self.charset.tree_pipe(),
+                    (false, false) => "   ",
+                    (true, true) => self.charset.tree_branch(),
+                    (true, false) => self.charset.tree_last(),
+                };
+            }
+
+            ret[idx] = prefix;
+            has_next.truncate(depth + 1);
+            has_next[depth] = true;
+        }
+
+        Some(ret)
+    }
+
+    /// Round the width of every column, including its padding, up to a multiple of the quantum,
+    /// so tables rendered from different data by the same tool look consistent. A quantum of 0
+    /// or 1 disables rounding.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, assert_grid_eq, grid, header};
+    ///
+    /// let mut grid = grid!(header!("name"), header!("size"), header!("owner")).unwrap();
+    /// add_line!(grid, "web-1", 10, "ops");
+    /// grid.set_width_quantum(8);
+    ///
+    /// assert_grid_eq!(
+    ///     grid,
+    ///     "
+    ///     name            size            owner
+    ///     -------------------------------------
+    ///     web-1           10              ops
+    ///     "
+    /// );
+    /// ```
+    pub fn set_width_quantum(&mut self, quantum: usize) {
+        self.width_quantum = quantum
+    }
+
+    /// Set which lines are measured to calculate column widths. For tables with hundreds of
+    /// thousands of lines, sampling trades exact widths for much faster layout.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, assert_grid_eq, grid, header, Sample};
+    ///
+    /// let mut grid = grid!(header!("name"), header!("size")).unwrap();
+    /// add_line!(grid, "web-1", 10);
+    /// add_line!(grid, "a-much-longer-name", 20);
+    ///
+    /// // the second line is not measured, so its name is clipped to the width of the first
+    /// grid.set_width_sampling(Sample::First(1));
+    /// assert_grid_eq!(
+    ///     grid,
+    ///     "
+    ///     name        size
+    ///     ----------------
+    ///     web-1       10
+    ///     a-mu…       20
+    ///     "
+    /// );
+    ///
+    /// grid.set_width_sampling(Sample::First(0));
+    /// assert!(grid.display().unwrap().contains("a-much-longer-name       20"));
+    /// ```
+    pub fn set_width_sampling(&mut self, sample: Sample) {
+        self.sample = sample
+    }
+
+    fn header_id(&self, index: usize) -> HeaderId {
+        let header = self.headers.0[index].borrow();
+        HeaderId {
+            index,
+            key: header.key().to_string(),
+            text: header.text.to_string(),
+        }
+    }
+
+    /// Yield the headers which were dropped by the last layout to fit the terminal, in the order
+    /// of the grid's columns.
+    pub fn dropped_headers(&self) -> Vec<HeaderId> {
+        self.candidates()
+            .into_iter()
+            .filter(|idx| !self.is_selected(self.headers.0[*idx].clone()))
+            .map(|idx| self.header_id(idx))
+            .collect()
+    }
+
+    /// Yield the width of the header's column, including the space between it and the next, as
+    /// laid out by the last call to [TTYGrid::display], [TTYGrid::write] or a similar method, so
+    /// other output can be aligned with the table. Columns which were not displayed yield [None].
+    /// [std::fmt::Display] renders the last layout as is, and does not change it; only grids which
+    /// were never laid out are laid out, on a copy, so this still yields [None] for them.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header};
+    ///
+    /// let name = header!("name");
+    /// let size = header!("size");
+    /// let mut grid = grid!(name.clone(), size.clone()).unwrap();
+    /// add_line!(grid, "web-1", 10);
+    /// assert_eq!(grid.column_width(name.clone()), None);
+    ///
+    /// let output = grid.display().unwrap();
+    /// let line = output.lines().nth(2).unwrap();
+    /// assert_eq!(grid.column_width(name), line.find("10"));
+    /// assert!(grid.total_width() <= 80);
+    ///
+    /// let key = header!("name");
+    /// let mut fresh = grid!(key.clone(), header!("size")).unwrap();
+    /// add_line!(fresh, "web-1", 10);
+    /// assert!(fresh.to_string().contains("web-1"));
+    /// assert_eq!(fresh.column_width(key), None);
+    /// ```
+    pub fn column_width(&self, header: SafeGridHeader) -> Option<usize> {
+        if !self.is_selected(header.clone()) {
+            return None;
+        }
+
+        let width = header.borrow().max_len;
+        width
+    }
+
+    /// Yield the width of the table as laid out by the last display; see
+    /// [TTYGrid::column_width]. Stacked tables (see [OverflowPolicy::Stack]) yield the width of
+    /// the first one.
+    pub fn total_width(&self) -> usize {
+        self.selected_widths().iter().sum()
+    }
+
+    fn total_width_of(&self, selected: &HeaderList) -> usize {
+        self.widths_of(selected).iter().sum()
+    }
+
+    /// Set a callback which is invoked when columns had to be dropped to fit the terminal, with
+    /// the dropped headers in the order of the grid's columns (or of the active view, see
+    /// [TTYGrid::set_active_view]), not the order they were dropped in. This is useful for telling
+    /// users that columns are hidden, and how to see them.
+    ///
+    /// ```
+    /// use std::{cell::RefCell, rc::Rc};
+    /// use ttygrid::{add_line, grid, header, test::FakeTerm, Priority};
+    ///
+    /// let mut grid = grid!(
+    ///     header!("name", Priority::Essential),
+    ///     header!("status"),
+    ///     header!("address")
+    /// )
+    /// .unwrap();
+    /// add_line!(grid, "web-1", "running", "10.0.0.4");
+    ///
+    /// let dropped = Rc::new(RefCell::new(Vec::new()));
+    /// let seen = dropped.clone();
+    /// grid.on_layout_degraded(move |ids| seen.borrow_mut().extend_from_slice(ids));
+    /// grid.refresh_width(&FakeTerm::new(20, 10)).unwrap();
+    /// assert_eq!(grid.display().unwrap(), "name\n-----\nweb-1\n");
+    ///
+    /// let dropped = dropped.borrow();
+    /// assert_eq!(dropped.iter().map(|id| id.index()).collect::<Vec<_>>(), vec![1, 2]);
+    /// assert_eq!(dropped.iter().map(|id| id.text()).collect::<Vec<_>>(), vec!["status", "address"]);
+    /// ```
+    pub fn on_layout_degraded(&mut self, callback: impl Fn(&[HeaderId]) + 'static) {
+        self.on_layout_degraded = Some(Rc::new(callback))
+    }
+
+    fn select_by_priority(
+        &mut self,
+        len_map: &mut LengthMapper,
+        candidates: HeaderList,
+    ) -> Result<()> {
+        self.deselect_all_headers();
+
+        if candidates.is_empty() {
+            return Err(self.terminal_too_small()?.into());
+        }
+
+        let mut candidates = candidates.0;
+        let mut columns = Vec::with_capacity(candidates.len());
+        let mut deprioritized = Vec::with_capacity(candidates.len());
+        for header in candidates.iter() {
+            let idx = self.header_index(header)?;
+            columns.push(Column::new(
+                len_map.max_len_for_column(&header.borrow())?,
+                self.layout_priority(idx),
+            ));
+            deprioritized.push(self.deprioritized(idx));
+        }
+
+        // deprioritized columns are dropped, from the right, before the engine drops any other.
+        let mut width = columns.iter().map(|column| column.width).sum::<usize>();
+        while width > self.width {
+            match deprioritized.iter().rposition(|d| *d) {
+                Some(pos) => {
+                    width -= columns.remove(pos).width;
+                    deprioritized.remove(pos);
+                    candidates.remove(pos);
+                }
+                None => break,
+            }
+        }
+
+        let layout = Engine::new(self.width).layout(&columns);
+
+        for (idx, column) in layout.columns.into_iter().enumerate() {
+            self.select(candidates[column].clone(), idx);
+        }
+
+        Ok(())
+    }
+
+    /// Set the colors of the header's column, which are only used by [TTYGrid::write]. See
+    /// [Style] for how they combine with the other colors of a cell.
+    pub fn set_column_colors(&mut self, header: SafeGridHeader, colors: Colors) -> Result<()> {
+        let idx = self.header_index(&header)?;
+        self.column_colors.retain(|(i, _)| *i != idx);
+        self.column_colors.push((idx, colors));
+        Ok(())
+    }
+
+    /// Color the numeric cells of the header's column on a gradient from min to max, which is
+    /// useful for latency or utilization columns. Cells which do not start with a number are
+    /// left alone. Only the foreground color is changed, and only by [TTYGrid::write].
+    ///
+    /// ```
+    /// use crossterm::style::{Color, Colored};
+    /// use ttygrid::{add_line, grid, header, test::FakeTerm, ColorScale};
+    ///
+    /// // colors are written even when NO_COLOR is set
+    /// Colored::set_ansi_color_disabled(false);
+    ///
+    /// let latency = header!("latency");
+    /// let mut term = FakeTerm::new(40, 10);
+    /// let mut grid = grid!(header!("host"), latency.clone()).unwrap();
+    /// grid.refresh_width(&term).unwrap();
+    /// add_line!(grid, "web-1", "10ms");
+    /// add_line!(grid, "web-2", "50ms");
+    /// add_line!(grid, "web-3", "90ms");
+    /// add_line!(grid, "web-4", "down");
+    /// grid.set_heatmap(latency, 0.0, 100.0, ColorScale::traffic_light()).unwrap();
+    ///
+    /// grid.write(&mut term).unwrap();
+    /// let colors = term
+    ///     .spans()
+    ///     .iter()
+    ///     .skip(2)
+    ///     .map(|spans| spans.last().unwrap().colors.foreground)
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(
+    ///     colors,
+    ///     vec![
+    ///         Some(Color::Green),
+    ///         Some(Color::Yellow),
+    ///         Some(Color::Red),
+    ///         Some(Color::Reset)
+    ///     ]
+    /// );
+    /// ```
+    pub fn set_heatmap(
+        &mut self,
+        header: SafeGridHeader,
+        min: f64,
+        max: f64,
+        scale: ColorScale,
+    ) -> Result<()> {
+        let idx = self.header_index(&header)?;
+        self.heatmaps.retain(|(i, _)| *i != idx);
+        self.heatmaps.push((idx, Heatmap { min, max, scale }));
+        Ok(())
+    }
+
+    /// Set when the terminal colors are reset while writing.
+    ///
+    /// ```
+    /// use crossterm::style::{Color, Colored, Colors};
+    /// use ttygrid::{add_line, grid, header, test::FakeTerm, Reset};
+    ///
+    /// // colors are written even when NO_COLOR is set
+    /// Colored::set_ansi_color_disabled(false);
+    ///
+    /// let written = |reset: Reset| {
+    ///     let mut term = FakeTerm::new(40, 10);
+    ///     let mut grid = grid!(header!("name"), header!("size")).unwrap();
+    ///     grid.refresh_width(&term).unwrap();
+    ///     grid.set_primary_color(Colors::new(Color::White, Color::Blue));
+    ///     add_line!(grid, "web-1", 10);
+    ///     grid.set_reset_policy(reset);
+    ///     grid.write(&mut term).unwrap();
+    ///     String::from_utf8(term.output().to_vec()).unwrap()
+    /// };
+    ///
+    /// let table = written(Reset::Table);
+    /// assert_eq!(table.matches("\x1b[0m").count(), 1);
+    /// assert!(table.ends_with("\n\x1b[0m"));
+    ///
+    /// let line = written(Reset::Line);
+    /// assert_eq!(line.matches("\x1b[0m\n").count(), 3);
+    ///
+    /// assert!(!written(Reset::Never).contains("\x1b[0m"));
+    /// ```
+    pub fn set_reset_policy(&mut self, reset: Reset) {
+        self.reset = reset
+    }
+
+    /// Compare the cells of this grid against another, typically an earlier version of the same
+    /// grid, yielding the changes in this grid.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, Change};
+    ///
+    /// let mut before = grid!(header!("name"), header!("state")).unwrap();
+    /// add_line!(before, "web-1", "up");
+    /// add_line!(before, "web-2", "up");
+    ///
+    /// let mut after = before.deep_clone();
+    /// after.clear_lines();
+    /// add_line!(after, "web-1", "up");
+    /// add_line!(after, "web-2", "down");
+    /// add_line!(after, "web-3", "up");
+    ///
+    /// let diff = after.diff(&before);
+    /// assert_eq!(diff.change(0, 1), Change::Same);
+    /// assert_eq!(diff.change(1, 0), Change::Same);
+    /// assert_eq!(diff.change(1, 1), Change::Changed);
+    /// assert_eq!(diff.change(2, 0), Change::Added);
+    /// assert_eq!(diff.changed_cells(), 3);
+    /// assert_eq!(before.diff(&after).removed_lines(), 1);
+    /// ```
+    pub fn diff(&self, other: &TTYGrid) -> GridDiff {
+        GridDiff::new(&self.lines, &other.lines)
+    }
+
+    /// Highlight the cells which changed since the last call to [TTYGrid::write] with the colors,
+    /// like `watch -d`. The grid remembers what it last wrote even when its lines are cleared, so
+    /// it can be refilled and written repeatedly.
+    ///
+    /// ```
+    /// use crossterm::style::{Color, Colored, Colors};
+    /// use ttygrid::{add_line, grid, header, test::FakeTerm};
+    ///
+    /// // colors are written even when NO_COLOR is set
+    /// Colored::set_ansi_color_disabled(false);
+    ///
+    /// let highlight = Colors::new(Color::Black, Color::Yellow);
+    /// let mut term = FakeTerm::new(40, 10);
+    /// let mut grid = grid!(header!("name"), header!("state")).unwrap();
+    /// grid.refresh_width(&term).unwrap();
+    /// grid.set_highlight_changes(Some(highlight));
+    /// add_line!(grid, "web-1", "up");
+    /// add_line!(grid, "web-2", "up");
+    /// grid.write(&mut term).unwrap();
+    ///
+    /// grid.clear_lines();
+    /// add_line!(grid, "web-1", "up");
+    /// add_line!(grid, "web-2", "down");
+    /// term.clear();
+    /// grid.write(&mut term).unwrap();
+    ///
+    /// let spans = term.spans();
+    /// assert!(spans[2].iter().all(|span| span.colors != highlight));
+    /// let changed = spans[3].last().unwrap();
+    /// assert_eq!((changed.text.as_str(), changed.colors), ("down", highlight));
+    /// ```
+    pub fn set_highlight_changes(&mut self, colors: Option<Colors>) {
+        self.highlight_changes = colors
+    }
+
+    /// Alternate between the primary and secondary colors of the theme whenever the item of the
+    /// header's column changes from one line to the next, instead of on every line, so lines
+    /// with the same value, such as the pods of a namespace, are grouped together. Lines are
+    /// expected to be ordered by the column; see [TTYGrid::sort_spec].
+    ///
+    /// ```
+    /// use crossterm::style::{Color, Colored, Colors};
+    /// use ttygrid::{add_line, grid, header, test::FakeTerm};
+    ///
+    /// // colors are written even when NO_COLOR is set
+    /// Colored::set_ansi_color_disabled(false);
+    ///
+    /// let namespace = header!("namespace");
+    /// let mut term = FakeTerm::new(40, 10);
+    /// let mut grid = grid!(namespace.clone(), header!("pod")).unwrap();
+    /// grid.refresh_width(&term).unwrap();
+    /// grid.set_primary_color(Colors::new(Color::White, Color::Black));
+    /// grid.set_secondary_color(Colors::new(Color::White, Color::DarkGrey));
+    /// add_line!(grid, "default", "web-1");
+    /// add_line!(grid, "default", "web-2");
+    /// add_line!(grid, "system", "dns-1");
+    /// grid.set_stripe_by(namespace).unwrap();
+    ///
+    /// grid.write(&mut term).unwrap();
+    /// let backgrounds = term
+    ///     .spans()
+    ///     .iter()
+    ///     .skip(2)
+    ///     .take(3)
+    ///     .map(|spans| spans[0].colors.background)
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(backgrounds[0], backgrounds[1]);
+    /// assert_ne!(backgrounds[1], backgrounds[2]);
+    /// ```
+    pub fn set_stripe_by(&mut self, header: SafeGridHeader) -> Result<()> {
+        self.stripe_by = Some(self.header_index(&header)?);
+        Ok(())
+    }
+
+    /// Go back to alternating the colors on every line.
+    pub fn clear_stripe_by(&mut self) {
+        self.stripe_by = None
+    }
+
+    // yields the stripe of each displayed line: its row, or with a column to stripe by, the
+    // number of times the column's item has changed since the first line.
+    fn stripes(&self, lines: &[(usize, GridLine)]) -> Vec<usize> {
+        let header = match self.stripe_by {
+            Some(idx) => &self.headers.0[idx],
+            None => return (0..lines.len()).collect(),
+        };
+
+        let mut stripe = 0;
+        let mut prev: Option<&str> = None;
+        lines
+            .iter()
+            .map(|(idx, _)| {
+                let contents = self.lines[*idx]
+                    .items
+                    .iter()
+                    .find(|item| Rc::ptr_eq(&item.header, header))
+                    .map(|item| item.contents.as_str())
+                    .unwrap_or_default();
+
+                if prev.is_some_and(|prev| prev != contents) {
+                    stripe += 1;
+                }
+                prev = Some(contents);
+                stripe
+            })
+            .collect()
+    }
+
+    // alternates between the primary and secondary colors of the theme.
+    fn stripe(&self, line_idx: usize) -> Colors {
+        if line_idx.is_multiple_of(2) {
+            self.theme.primary
+        } else {
+            self.theme.secondary
+        }
+    }
+
+    // yields the colors of the cell by source; see Style for the precedence. Stripes alternate by
+    // the stripe of the displayed line (see stripes), while changes are found by the position of
+    // the line.
+    fn cell_style(
+        &self,
+        stripe: usize,
+        line_idx: usize,
+        line: &GridLine,
+        item: &GridItem,
+    ) -> Style {
+        let mut style = Style {
+            theme: None,
+            stripe: Some(self.stripe(stripe)),
+            column: None,
+            row: line.colors,
+            rule: None,
+            cell: item.colors,
+        };
+
+        let idx = match self.header_index(&item.header) {
+            Ok(idx) => idx,
+            Err(_) => return style,
+        };
+
+        style.column = self
+            .column_colors
+            .iter()
+            .find(|(i, _)| *i == idx)
+            .map(|(_, colors)| *colors);
+
+        style.rule = self
+            .heatmaps
+            .iter()
+            .find(|(i, _)| *i == idx)
+            .and_then(|(_, heatmap)| heatmap.color_for(&item.contents))
+            .map(|color| Colors {
+                foreground: Some(color),
+                background: None,
+            })
+            .or_else(|| self.vocabulary_colors(idx, &item.contents));
+
+        if let Some(markers) = self.marker_column(idx) {
+            style.rule = markers.colors(&item.contents);
+        }
+
+        if let (Some(colors), Some(changes)) = (self.highlight_changes, &self.changes) {
+            if changes.change(line_idx, idx) != Change::Same {
+                style.rule = Some(colors);
+            }
+        }
+
+        style
+    }
+
+    // yields the lines as they should be displayed, with per-column display options applied,
+    // along with their positions. Hidden and filtered out lines are skipped.
+    fn display_lines(&self) -> Vec<(usize, GridLine)> {
+        self.display_lines_of(&self.selected)
+    }
+
+    fn display_lines_of(&self, selected: &HeaderList) -> Vec<(usize, GridLine)> {
+        let mut ret = Vec::with_capacity(self.lines.len());
+        let mut prev: Option<&GridLine> = None;
+        let tree = self.tree_prefixes();
+
+        for line_idx in self.line_order() {
+            let line = &self.lines[line_idx];
+            if !self.line_shown(line_idx) {
+                continue;
+            }
+
+            let mut display = line.clone();
+
+            for item in display.items.iter_mut() {
+                if let Cow::Owned(redacted) = self.redacted(&item.header, &item.contents) {
+                    item.contents = redacted;
+                    item.spans = None;
+                    item.raw = None;
+                }
+            }
+
+            if let Some(prev) = prev {
+                for (idx, style) in self.dedup.iter() {
+                    if let (Some(item), Some(prev_item)) =
+                        (display.items.get_mut(*idx), prev.items.get(*idx))
+                    {
+                        if item.contents == prev_item.contents {
+                            item.contents = style.mark().to_string();
+                        }
+                    }
+                }
+            }
+
+            for item in display.items.iter_mut() {
+                if let (Some(values), Some(max_len)) = (&item.sparkline, item.max_len) {
+                    let pad = item.header.borrow().max_pad.unwrap_or(0) + 2;
+                    item.contents =
+                        render_sparkline(values, max_len.saturating_sub(pad + 1), self.charset);
+                }
+            }
+
+            // unicode icons are declared two cells wide, as emoji are displayed
+            for (idx, icons, _) in self.icons.iter() {
+                if let Some(item) = display.items.get_mut(*idx) {
+                    if let Some(icon) = icons.icon(&item.contents, self.charset) {
+                        item.contents = icon.to_string();
+                        item.spans = None;
+                        item.raw = (self.charset == Charset::Unicode).then_some(2);
+                    }
+                }
+            }
+
+            if let (Some(tree), Some(tree_column)) = (&tree, self.tree_column) {
+                if let Some(item) = display.items.get_mut(tree_column) {
+                    item.contents = tree[line_idx].clone() + &item.contents;
+                }
+            }
+
+            let mut display = display.selected(selected);
+
+            if let (Some(item), true) = (display.items.first_mut(), line.indent > 0) {
+                item.contents = self.indent.repeat(line.indent) + &item.contents;
+            }
+
+            if !self.wrap {
+                for item in display.items.iter_mut().filter(|item| item.raw.is_none()) {
+                    let header = item.header.borrow();
+                    // items of lines which were not measured may be wider than their column
+                    let sampled = header
+                        .max_len
+                        .filter(|_| !self.sample.measures_all())
+                        .map(|len| len.saturating_sub(header.gap()));
+                    if let Some(width) = header.content_width().or(sampled) {
+                        let contents = header.abbreviate(&item.contents, width);
+                        item.contents = clip(&contents, width, self.charset);
+                    }
+                }
+            }
+
+            if self.trim_trailing_whitespace {
+                if let Some(item) = display.items.last_mut() {
+                    item.trim = true;
+                }
+            }
+
+            ret.push((line_idx, display));
+            prev = Some(line);
+        }
+
+        ret
+    }
+
+    /// Yield a string which is suitable for passing to [println!], but does not make any attempt
     /// to add terminal styling, which may be better for situations where data is piped. Unlike
     /// [std::fmt::Display], this display method returns `Result<String, anyhow::Error>`.
     pub fn display(&mut self) -> Result<String> {
+        let start = std::time::Instant::now();
         self.determine_headers()?;
-        Ok(format!("{}", self))
+        let ret = self.render()?;
+        self.notify_render_complete(start);
+        Ok(ret)
+    }
+
+    // renders every table of the grid as it was last laid out, followed by the legend and the
+    // status line.
+    fn render(&self) -> Result<String> {
+        let tables = self
+            .layouts()
+            .iter()
+            .map(|layout| self.render_table(layout))
+            .collect::<Result<Vec<String>>>()?;
+
+        let mut ret = tables.join("\n");
+        for line in self.legend_lines().into_iter().chain(self.status_line()) {
+            ret.push_str(&line);
+            ret.push('\n');
+        }
+
+        Ok(ret)
+    }
+
+    /// Set a status line which is displayed under the table and its legend, such as a summary
+    /// of the lines or of the filters applied. The line is shortened with an ellipsis to fit the
+    /// terminal, so it never wraps, and line breaks in the text are displayed as spaces. The
+    /// colors are only used by [TTYGrid::write]. It is not displayed by the [Pager].
+    ///
+    /// ```
+    /// use crossterm::style::{Color, Colors};
+    /// use ttygrid::{add_line, grid, header};
+    ///
+    /// let mut grid = grid!(header!("name")).unwrap();
+    /// add_line!(grid, "web-1");
+    /// grid.set_status_line(
+    ///     format!("1 item, filtered by name~web{}", " ".repeat(100)),
+    ///     Colors::new(Color::Black, Color::White),
+    /// );
+    ///
+    /// let output = grid.display().unwrap();
+    /// let status = output.lines().last().unwrap();
+    /// assert!(status.starts_with("1 item, filtered by name~web"));
+    /// assert_eq!(status.chars().count(), 80);
+    /// ```
+    pub fn set_status_line(&mut self, text: impl Into<String>, colors: Colors) {
+        self.status = Some((text.into(), colors))
+    }
+
+    /// Remove the status line.
+    pub fn clear_status_line(&mut self) {
+        self.status = None
+    }
+
+    // the status line, shortened to the width.
+    fn status_line(&self) -> Option<String> {
+        self.status
+            .as_ref()
+            .map(|(text, _)| clip(&text.replace(['\r', '\n'], " "), self.width, self.charset))
+    }
+
+    /// Add an entry to the legend, which explains the symbols used in the table, such as `*` for
+    /// a default. The legend is displayed under the table, with as many entries on each line as
+    /// fit in the terminal; entries which are longer than the terminal is wide are wrapped (see
+    /// [TTYGrid::set_word_break]). It is not displayed by the [Pager].
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, test::FakeTerm};
+    ///
+    /// let mut grid = grid!(header!("name")).unwrap();
+    /// add_line!(grid, "web-1 *");
+    /// add_line!(grid, "web-2 !");
+    /// grid.add_legend("*", "default");
+    /// grid.add_legend("!", "failing");
+    ///
+    /// let output = grid.display().unwrap();
+    /// assert_eq!(output.lines().last(), Some("* = default  ! = failing"));
+    ///
+    /// grid.add_legend("?", "the state of the host is unknown");
+    /// grid.refresh_width(&FakeTerm::new(20, 10)).unwrap();
+    /// let output = grid.display().unwrap();
+    /// let legend = output.lines().skip(4).collect::<Vec<&str>>();
+    /// let wrapped = ["* = default", "! = failing", "? = the state of the", "    host is unknown"];
+    /// assert_eq!(legend, wrapped);
+    /// ```
+    pub fn add_legend(&mut self, symbol: impl Into<String>, description: impl Into<String>) {
+        self.legend.push((symbol.into(), description.into()))
+    }
+
+    /// Remove all entries of the legend.
+    pub fn clear_legend(&mut self) {
+        self.legend.clear()
+    }
+
+    // lays out the legend entries into lines which fit the width. Entries which are wider than
+    // the terminal are put on lines of their own.
+    fn legend_lines(&self) -> Vec<String> {
+        let mut ret: Vec<String> = Vec::new();
+
+        for (symbol, description) in self.legend.iter() {
+            let entry = format!("{} = {}", symbol, description);
+            match ret.last_mut() {
+                Some(line) if line.chars().count() + 2 + entry.chars().count() <= self.width => {
+                    line.push_str("  ");
+                    line.push_str(&entry);
+                }
+                _ if entry.chars().count() <= self.width => ret.push(entry),
+                // entries too long for the terminal are wrapped, with the description continued
+                // under itself when there is room for it
+                _ => {
+                    let label = format!("{} = ", symbol);
+                    let indent = label.chars().count();
+                    if indent < self.width {
+                        let lines = wrap_text(description, self.width - indent, self.word_break);
+                        for (n, line) in lines.into_iter().enumerate() {
+                            match n {
+                                0 => ret.push(format!("{}{}", label, line)),
+                                _ => ret.push(" ".repeat(indent) + line.trim_start()),
+                            }
+                        }
+                    } else {
+                        ret.extend(wrap_text(&entry, self.width.max(1), self.word_break));
+                    }
+                }
+            }
+        }
+
+        ret
+    }
+
+    // renders the selected headers as a table, without terminal styling.
+    fn render_table(&self, selected: &HeaderList) -> Result<String> {
+        use std::fmt::Write;
+
+        let mut ret = String::new();
+        let mut header = String::new();
+        if self.show_header {
+            writeln!(header, "{}", self.header_line_of(selected))?;
+            let width = self.delimiter_width_of(selected);
+            writeln!(header, "{:-<width$}", "-", width = width)?;
+        }
+        ret.push_str(&header);
+
+        let widths = self.widths_of(selected);
+
+        for (idx, (_, line)) in self.display_lines_of(selected).into_iter().enumerate() {
+            if self.repeats_header_before(idx) {
+                ret.push_str(&header);
+            }
+
+            match &self.row_renderer {
+                Some(renderer) => {
+                    let mut buf = Vec::new();
+                    renderer(&line, &widths, &mut buf)?;
+                    writeln!(ret, "{}", String::from_utf8_lossy(&buf))?
+                }
+                None => {
+                    for physical in self.physical_lines(&line) {
+                        writeln!(ret, "{}", physical)?
+                    }
+                }
+            }
+        }
+
+        let margin = self.table_margin_of(selected);
+        if margin > 0 {
+            let margin = " ".repeat(margin);
+            ret = ret.lines().map(|l| format!("{}{}\n", margin, l)).collect();
+        }
+
+        Ok(ret)
+    }
+
+    /// Display the header's column at the right edge of the terminal, like the dates of `ls -l`,
+    /// by widening the gap before it to take the space the table would otherwise leave. Columns
+    /// after it follow it, so the column is usually the last one; right aligned contents of the
+    /// last column then end exactly at the edge, as long as trailing whitespace is trimmed (see
+    /// [TTYGrid::set_trim_trailing_whitespace]). Only one column is flush right at a time, and
+    /// the first column displayed can not be.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header};
+    ///
+    /// let modified = header!("modified", align = right);
+    /// let mut grid = grid!(header!("name"), modified.clone()).unwrap();
+    /// add_line!(grid, "notes.txt", "Oct 14 09:30");
+    /// grid.set_flush_right(modified).unwrap();
+    ///
+    /// let output = grid.display().unwrap();
+    /// let line = output.lines().nth(2).unwrap();
+    /// assert!(line.starts_with("notes.txt  "));
+    /// assert!(line.ends_with(" Oct 14 09:30"));
+    /// assert_eq!(line.chars().count(), 80);
+    /// ```
+    pub fn set_flush_right(&mut self, header: SafeGridHeader) -> Result<()> {
+        self.flush_right = Some(self.header_index(&header)?);
+        Ok(())
+    }
+
+    /// Stop displaying any column at the right edge of the terminal; see
+    /// [TTYGrid::set_flush_right].
+    pub fn clear_flush_right(&mut self) {
+        self.flush_right = None
+    }
+
+    /// Position the table horizontally when it is narrower than the terminal, such as to center
+    /// a short summary like a banner. The legend and the status line are not moved.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, TableAlign};
+    ///
+    /// let mut grid = grid!(header!("name"), header!("size")).unwrap();
+    /// add_line!(grid, "web", 10);
+    /// grid.set_table_alignment(TableAlign::Right);
+    ///
+    /// let output = grid.display().unwrap();
+    /// let lines = output.lines().collect::<Vec<&str>>();
+    /// assert_eq!(lines[0].trim_start(), "name      size");
+    /// assert_eq!(lines[0].chars().count(), 80);
+    /// assert_eq!(lines[1].trim_start().len(), 14);
+    /// assert_eq!(lines[2].find("web"), lines[0].find("name"));
+    /// ```
+    pub fn set_table_alignment(&mut self, align: TableAlign) {
+        self.table_align = align
+    }
+
+    pub fn table_alignment(&self) -> TableAlign {
+        self.table_align
+    }
+
+    // the width the table is displayed in: from the first column to the end of the longest of
+    // the items and the label of the last column.
+    fn table_width(&self, selected: &HeaderList) -> usize {
+        // the last column's padding, and any width the quantum added to it, is not displayed
+        let gap = selected
+            .0
+            .last()
+            .map(|h| {
+                let rounding = self
+                    .rounding
+                    .iter()
+                    .find(|(idx, _)| Rc::ptr_eq(&self.headers.0[*idx], h))
+                    .map(|(_, rounding)| *rounding)
+                    .unwrap_or_default();
+                h.borrow().gap() + rounding
+            })
+            .unwrap_or_default();
+        let label = if self.show_header {
+            self.header_line_of(selected).trim_end().chars().count()
+        } else {
+            0
+        };
+
+        self.total_width_of(selected).saturating_sub(gap).max(label)
+    }
+
+    // the spaces before each row of the table, to position it; see set_table_alignment.
+    fn table_margin(&self) -> usize {
+        self.table_margin_of(&self.selected)
+    }
+
+    fn table_margin_of(&self, selected: &HeaderList) -> usize {
+        let spare = self.width.saturating_sub(self.table_width(selected));
+        match self.table_align {
+            TableAlign::Left => 0,
+            TableAlign::Center => spare / 2,
+            TableAlign::Right => spare,
+        }
+    }
+
+    // the width of the delimiter between the header and the lines: the width of the table as laid
+    // out, so it ends with the last column displayed.
+    fn delimiter_width(&self) -> usize {
+        self.delimiter_width_of(&self.selected)
+    }
+
+    fn delimiter_width_of(&self, selected: &HeaderList) -> usize {
+        self.table_width(selected).min(self.width).max(1)
+    }
+
+    // whether the header rows are repeated before the line; see set_repeat_header_every.
+    fn repeats_header_before(&self, idx: usize) -> bool {
+        self.show_header
+            && idx > 0
+            && self
+                .repeat_header
+                .is_some_and(|n| n > 0 && idx.is_multiple_of(n))
+    }
+
+    // yields the styled rows with the header rows repeated as set by set_repeat_header_every.
+    fn with_repeated_header(&self, rows: Vec<(Row, Segments)>) -> Vec<(Row, Segments)> {
+        let header = rows
+            .iter()
+            .take_while(|(row, _)| !matches!(row, Row::Line(_)))
+            .cloned()
+            .collect::<Vec<(Row, Segments)>>();
+
+        let mut ret = Vec::with_capacity(rows.len());
+        let (mut last, mut count) = (None, 0);
+        for (row, segments) in rows {
+            if matches!(row, Row::Line(_)) && Some(row) != last {
+                if self.repeats_header_before(count) {
+                    ret.extend(header.iter().cloned());
+                }
+                last = Some(row);
+                count += 1;
+            }
+            ret.push((row, segments));
+        }
+
+        ret
+    }
+
+    // yields each line of output as a series of colored segments, without newlines.
+    fn styled_lines(&self) -> Result<Vec<Vec<(Colors, String)>>> {
+        Ok(self
+            .styled_rows()?
+            .into_iter()
+            .map(|(_, row)| row)
+            .collect())
+    }
+
+    // yields each line of output like styled_lines, with what it displays.
+    fn styled_rows(&self) -> Result<Vec<(Row, Segments)>> {
+        let mut ret = Vec::new();
+        if self.show_header {
+            ret.push((Row::Header, vec![(self.theme.header, self.header_line())]));
+            ret.push((
+                Row::Delimiter,
+                vec![(
+                    self.theme.delimiter,
+                    format!("{:-<width$}", "-", width = self.delimiter_width()),
+                )],
+            ));
+        }
+
+        let widths = self.selected_widths();
+
+        let lines = self.display_lines();
+        let stripes = self.stripes(&lines);
+        for ((idx, line), row) in lines.iter().zip(stripes) {
+            let idx = *idx;
+            if let Some(renderer) = &self.row_renderer {
+                let colors = Style {
+                    stripe: Some(self.stripe(row)),
+                    row: line.colors,
+                    ..Default::default()
+                }
+                .resolve();
+
+                let mut buf = Vec::new();
+                renderer(line, &widths, &mut buf)?;
+                ret.push((
+                    Row::Line(idx),
+                    vec![(colors, String::from_utf8_lossy(&buf).to_string())],
+                ));
+                continue;
+            }
+
+            let styles = line
+                .items
+                .iter()
+                .map(|item| self.cell_style(row, idx, line, item).resolve())
+                .collect::<Vec<Colors>>();
+
+            for physical in self.physical_lines(line) {
+                ret.push((
+                    Row::Line(idx),
+                    physical
+                        .items
+                        .iter()
+                        .zip(styles.iter())
+                        .flat_map(|(item, colors)| item.segments(*colors))
+                        .collect(),
+                ));
+            }
+        }
+
+        let margin = self.table_margin();
+        if margin > 0 {
+            let reset = Colors::new(Color::Reset, Color::Reset);
+            for (_, segments) in ret.iter_mut() {
+                segments.insert(0, (reset, " ".repeat(margin)));
+            }
+        }
+
+        for (colors, _) in ret.iter_mut().flat_map(|(_, segments)| segments.iter_mut()) {
+            *colors = self.displayable(*colors);
+        }
+
+        Ok(ret)
+    }
+
+    /// Emit the grid through the [log] crate, one record per line, without any terminal styling.
+    /// This allows diagnostic tables to be embedded in structured logging (tracing users can
+    /// collect them with `tracing-log`). Requires the `log` feature.
+    #[cfg(feature = "log")]
+    pub fn log(&mut self, level: log::Level, target: &str) -> Result<()> {
+        for line in self.display()?.lines() {
+            log::log!(target: target, level, "{}", line);
+        }
+
+        Ok(())
     }
 
     /// Write to the writer, typically [std::io::stdout]. Terminal colors will be set.
     pub fn write(&mut self, mut writer: impl std::io::Write) -> Result<()> {
+        self.queue_into(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Queue the commands which write the grid onto the writer, like [crossterm::queue!], without
+    /// flushing it. Applications which batch their own crossterm commands can interleave the grid
+    /// with them, and flush once.
+    ///
+    /// ```
+    /// use crossterm::style::Colored;
+    /// use ttygrid::{add_line, grid, header};
+    ///
+    /// // colors are written even when NO_COLOR is set
+    /// Colored::set_ansi_color_disabled(false);
+    ///
+    /// let mut grid = grid!(header!("name"), header!("size")).unwrap();
+    /// add_line!(grid, "web-1", 10);
+    ///
+    /// let mut out: Vec<u8> = Vec::new();
+    /// grid.queue_into(&mut out).unwrap();
+    ///
+    /// // each line starts by resetting the colors, and the terminal is reset at the end
+    /// let reset = "\x1b[39m\x1b[49m";
+    /// let expected = format!(
+    ///     "{r}name        size\n{r}----------------\n{r}web-1       {r}10\n\x1b[0m",
+    ///     r = reset
+    /// );
+    /// assert_eq!(String::from_utf8(out).unwrap(), expected);
+    /// ```
+    pub fn queue_into(&mut self, mut writer: impl std::io::Write) -> Result<()> {
+        let start = std::time::Instant::now();
         self.determine_headers()?;
-        execute!(
-            writer,
-            SetColors(self.header_color),
-            Print(&format!("{}\n", self.selected))
-        )?;
-        execute!(
-            writer,
-            SetColors(self.delimiter_color),
-            Print(&format!("{:-<width$}\n", "-", width = self.width))
-        )?;
 
-        for (idx, line) in self.lines.iter().enumerate() {
-            if idx % 2 == 0 {
-                execute!(writer, SetColors(self.primary_color))?;
-            } else {
-                execute!(writer, SetColors(self.secondary_color))?;
+        if self.highlight_changes.is_some() {
+            self.changes = self
+                .last_written
+                .as_ref()
+                .map(|last| GridDiff::new(&self.lines, last));
+            self.last_written = Some(self.lines.clone());
+        }
+
+        let selected = self.selected.clone();
+        let mut lines = Vec::new();
+        for (idx, layout) in self.layouts().into_iter().enumerate() {
+            if idx > 0 {
+                lines.push((Row::Other, Vec::new()));
+            }
+
+            self.selected = layout;
+            lines.extend(self.with_repeated_header(self.styled_rows()?));
+        }
+        self.selected = selected;
+
+        let reset = Colors::new(Color::Reset, Color::Reset);
+        lines.extend(
+            self.legend_lines()
+                .into_iter()
+                .map(|l| (Row::Other, vec![(reset, l)])),
+        );
+        if let (Some(line), Some((_, colors))) = (self.status_line(), &self.status) {
+            lines.push((Row::Other, vec![(self.displayable(*colors), line)]));
+        }
+
+        for (row, line) in lines {
+            let underline = row == Row::Header && self.underline_header;
+            if underline {
+                queue!(writer, SetAttribute(Attribute::Underlined))?;
+            }
+
+            let dim = matches!(row, Row::Line(idx) if self.line_dimmed(idx));
+            if dim {
+                queue!(writer, SetAttribute(Attribute::Dim))?;
+            }
+
+            for (colors, text) in line {
+                queue!(writer, SetColors(colors), Print(text))?;
+            }
+
+            if underline {
+                queue!(writer, SetAttribute(Attribute::NoUnderline))?;
+            }
+
+            if dim {
+                queue!(writer, SetAttribute(Attribute::NormalIntensity))?;
+            }
+
+            if self.reset == Reset::Line {
+                queue!(writer, ResetColor)?;
+            }
+
+            queue!(writer, Print("\n"))?;
+        }
+
+        if self.reset == Reset::Table {
+            queue!(writer, ResetColor)?;
+        }
+
+        self.notify_render_complete(start);
+        Ok(())
+    }
+
+    /// Write the grid to a file at the path, laid out for the width instead of the terminal, so
+    /// reports do not depend on the terminal the program happens to run in. The file is created,
+    /// or truncated if it exists.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, ColorChoice};
+    ///
+    /// let mut grid = grid!(header!("name"), header!("description")).unwrap();
+    /// add_line!(grid, "a", "x".repeat(100));
+    ///
+    /// let path = std::env::temp_dir().join("ttygrid-write-file.txt");
+    /// grid.write_file(&path, 120, ColorChoice::Never).unwrap();
+    ///
+    /// let report = std::fs::read_to_string(&path).unwrap();
+    /// assert!(report.starts_with("name    description"));
+    /// assert!(!report.contains('\x1b'));
+    /// # std::fs::remove_file(path).unwrap();
+    /// ```
+    pub fn write_file(
+        &mut self,
+        path: impl AsRef<std::path::Path>,
+        width: usize,
+        colors: ColorChoice,
+    ) -> Result<()> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let terminal_width = std::mem::replace(&mut self.width, width);
+
+        let res = match colors {
+            ColorChoice::Never => self.write_plain(&mut writer),
+            ColorChoice::Always => self.write(&mut writer),
+        };
+        self.width = terminal_width;
+
+        res
+    }
+
+    /// Write the grid into a rectangle of the terminal, whose top left corner is at column x and
+    /// row y. The layout is calculated for the width of the rectangle instead of the terminal, and
+    /// lines which do not fit in the rectangle are clipped. This is useful for embedding the grid
+    /// into a larger terminal interface.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, test::FakeTerm};
+    ///
+    /// let mut grid = grid!(header!("name"), header!("description")).unwrap();
+    /// add_line!(grid, "web-1", "the frontend server");
+    /// add_line!(grid, "db-1", "the database");
+    ///
+    /// // the rows and columns the cursor was moved to, with the text written at each
+    /// let writes = |term: &FakeTerm| {
+    ///     let output = String::from_utf8(term.output().to_vec()).unwrap();
+    ///     let mut ret: Vec<(u32, u32, String)> = Vec::new();
+    ///     for code in output.split("\x1b[").skip(1) {
+    ///         let end = code.find(|c: char| c.is_ascii_alphabetic()).unwrap();
+    ///         let (params, text) = (&code[..end], &code[end + 1..]);
+    ///         if code[end..].starts_with('H') {
+    ///             let (row, column) = params.split_once(';').unwrap();
+    ///             ret.push((row.parse().unwrap(), column.parse().unwrap(), String::new()));
+    ///         }
+    ///         if let Some(last) = ret.last_mut() {
+    ///             last.2.push_str(text);
+    ///         }
+    ///     }
+    ///     ret
+    /// };
+    ///
+    /// // lines are clipped to the width of the rectangle, and lines past its height are dropped
+    /// grid.set_row_renderer(|line, _, writer| {
+    ///     write!(writer, "{} is a server", line.items()[0].contents())?;
+    ///     Ok(())
+    /// });
+    /// let mut term = FakeTerm::new(80, 24);
+    /// grid.write_region(&mut term, 2, 0, 12, 3).unwrap();
+    /// assert_eq!(
+    ///     writes(&term),
+    ///     vec![
+    ///         (1, 3, "name".to_string()),
+    ///         (2, 3, "-----".to_string()),
+    ///         (3, 3, "web-1 is a s".to_string()),
+    ///     ]
+    /// );
+    ///
+    /// // rows past the last row of the terminal are not written
+    /// let mut term = FakeTerm::new(80, 24);
+    /// grid.write_region(&mut term, 0, u16::MAX - 3, 40, 4).unwrap();
+    /// let rows = writes(&term).iter().map(|(row, _, _)| *row).collect::<Vec<_>>();
+    /// assert_eq!(rows, vec![65533, 65534, 65535]);
+    /// ```
+    pub fn write_region(
+        &mut self,
+        mut writer: impl std::io::Write,
+        x: u16,
+        y: u16,
+        width: u16,
+        height: u16,
+    ) -> Result<()> {
+        let start = std::time::Instant::now();
+        let terminal_width = std::mem::replace(&mut self.width, width as usize);
+        let lines = self.determine_headers().and_then(|_| self.styled_lines());
+        self.width = terminal_width;
+
+        for (row, line) in lines?.into_iter().take(height as usize).enumerate() {
+            // rows past the last one the terminal can address are not written
+            let row = match y.checked_add(row as u16).filter(|row| *row < u16::MAX) {
+                Some(row) => row,
+                None => break,
+            };
+            execute!(writer, MoveTo(x, row))?;
+            write_clipped(&mut writer, line, width as usize)?;
+
+            if self.reset == Reset::Line {
+                execute!(writer, ResetColor)?;
             }
-            execute!(writer, Print(&format!("{}\n", line.selected(self))))?;
         }
 
+        if self.reset == Reset::Table {
+            execute!(writer, ResetColor)?;
+        }
+
+        self.notify_render_complete(start);
         Ok(())
     }
 }
 
+// wraps text to the width. With the textwrap feature lines are broken between words; otherwise
+// they are only broken between words when hyphenating.
+#[cfg(feature = "textwrap")]
+fn wrap_text(text: &str, width: usize, word_break: WordBreak) -> Vec<String> {
+    let hyphenate = word_break == WordBreak::Hyphenate && width > 1;
+    let splitter = match word_break {
+        WordBreak::Anywhere => textwrap::WordSplitter::NoHyphenation,
+        WordBreak::Hyphenate => textwrap::WordSplitter::HyphenSplitter,
+    };
+
+    let options = textwrap::Options::new(width)
+        .break_words(!hyphenate)
+        .word_splitter(splitter);
+
+    let lines = textwrap::wrap(text, options);
+    if !hyphenate {
+        return lines.into_iter().map(|line| line.into_owned()).collect();
+    }
+
+    // words which are still too long are left on lines of their own, and broken here so each
+    // line but the last ends with a hyphen.
+    let mut ret = Vec::new();
+    for line in lines {
+        let mut rest = &line.chars().collect::<Vec<char>>()[..];
+        while rest.len() > width {
+            ret.push(rest[..width - 1].iter().chain(['-'].iter()).collect());
+            rest = &rest[width - 1..];
+        }
+        ret.push(rest.iter().collect());
+    }
+    ret
+}
+
+#[cfg(not(feature = "textwrap"))]
+fn wrap_text(text: &str, width: usize, word_break: WordBreak) -> Vec<String> {
+    let chars = text.chars().collect::<Vec<char>>();
+
+    match word_break {
+        WordBreak::Hyphenate if width > 1 => {
+            let mut ret = Vec::new();
+            let mut rest = &chars[..];
+
+            while rest.len() > width {
+                // break at a space or after a hyphen if there is one, otherwise add a hyphen
+                let space = rest[..=width].iter().rposition(|c| *c == ' ');
+                let hyphen = rest[..width].iter().rposition(|c| *c == '-');
+
+                match (space, hyphen) {
+                    (Some(space), hyphen) if hyphen.is_none_or(|h| space > h) => {
+                        ret.push(rest[..space].iter().collect());
+                        rest = &rest[space + 1..];
+                    }
+                    (_, Some(idx)) => {
+                        ret.push(rest[..=idx].iter().collect());
+                        rest = &rest[idx + 1..];
+                    }
+                    _ => {
+                        ret.push(rest[..width - 1].iter().chain(['-'].iter()).collect());
+                        rest = &rest[width - 1..];
+                    }
+                }
+            }
+
+            ret.push(rest.iter().collect());
+            ret
+        }
+        _ => chars.chunks(width).map(|c| c.iter().collect()).collect(),
+    }
+}
+
+// shortens the text to the width, ending it with an ellipsis if it was too long.
+fn clip(text: &str, width: usize, charset: Charset) -> String {
+    clip_with(text, width, charset.ellipsis())
+}
+
+// shortens the text to the width, ending it with the ellipsis if it was too long.
+fn clip_with(text: &str, width: usize, ellipsis: &str) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+
+    let keep = width.saturating_sub(ellipsis.chars().count());
+    text.chars()
+        .take(keep)
+        .chain(ellipsis.chars())
+        .take(width)
+        .collect()
+}
+
+// the width of the contents of an item, including its right padding. Items with several lines
+// are only as wide as their longest line when wrapped.
+fn content_len(contents: &str) -> usize {
+    let longest = contents.split('\n').map(|l| l.chars().count()).max();
+    longest.unwrap_or_default() + 1
+}
+
+// yields the widths of the items of the lines at the indices.
+#[cfg(not(feature = "rayon"))]
+fn item_lengths(lines: &[GridLine], indices: &[usize]) -> Vec<Vec<usize>> {
+    indices
+        .iter()
+        .map(|i| lines[*i].items.iter().map(GridItem::len).collect())
+        .collect()
+}
+
+// yields the widths of the items of the lines at the indices, measuring the contents on all
+// cores. Items can not be shared between threads, as they refer to their headers, so only the
+// contents are handed to rayon.
+#[cfg(feature = "rayon")]
+fn item_lengths(lines: &[GridLine], indices: &[usize]) -> Vec<Vec<usize>> {
+    use rayon::prelude::*;
+
+    let contents = indices
+        .iter()
+        .flat_map(|i| {
+            lines[*i]
+                .items
+                .iter()
+                .map(|item| (item.raw, item.contents.as_str()))
+        })
+        .collect::<Vec<(Option<usize>, &str)>>();
+
+    let mut lens = contents
+        .par_iter()
+        .with_min_len(4096)
+        .map(|(raw, c)| raw.map_or_else(|| content_len(c), |width| width + 1))
+        .collect::<Vec<usize>>()
+        .into_iter();
+
+    indices
+        .iter()
+        .map(|i| lens.by_ref().take(lines[*i].items.len()).collect())
+        .collect()
+}
+
+// writes the segments of a line, stopping once width characters have been written.
+fn write_clipped(
+    mut writer: impl std::io::Write,
+    line: Vec<(Colors, String)>,
+    width: usize,
+) -> Result<()> {
+    let mut remaining = width;
+    for (colors, text) in line {
+        if remaining == 0 {
+            break;
+        }
+
+        let clipped = text.chars().take(remaining).collect::<String>();
+        remaining -= clipped.chars().count();
+        execute!(writer, SetColors(colors), Print(clipped))?;
+    }
+
+    Ok(())
+}
+
+/// Formatting a grid renders it as it was last laid out by [TTYGrid::display] or
+/// [TTYGrid::write], without changing it or notifying its observers, so it is cheap to do
+/// repeatedly; lines added since are aligned to the columns of that layout. A grid which has not
+/// been laid out yet is laid out on a copy first, without notifying anyone. If the grid cannot be
+/// laid out, such as when the terminal is too small, the error is formatted instead.
+///
+/// ```
+/// use ttygrid::{add_line, grid, header};
+///
+/// let mut grid = grid!(header!("name"), header!("size")).unwrap();
+/// add_line!(grid, "a", 10);
+///
+/// assert!(format!("{}", grid).starts_with("name"));
+/// assert_eq!(format!("{}", grid), grid.display().unwrap());
+/// assert_eq!(format!("{}", grid), grid.display().unwrap());
+/// ```
 impl fmt::Display for TTYGrid {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        writeln!(formatter, "{}", self.selected)?;
-        writeln!(formatter, "{:-<width$}", "-", width = self.width)?;
+        let output = if self.selected.is_empty() {
+            let mut grid = self.deep_clone();
+            grid.observers.clear();
+            grid.on_layout_degraded = None;
+            grid.display()
+        } else {
+            self.render()
+        };
 
-        for line in self.lines.clone() {
-            writeln!(formatter, "{}", line.selected(self))?
+        match output {
+            Ok(output) => write!(formatter, "{}", output),
+            Err(e) => writeln!(formatter, "{}", e),
         }
-
-        Ok(())
     }
 }
 
+/// TtyGridRow is implemented by types which describe a row of a grid, typically with
+/// `#[derive(TtyGridRow)]` from the `derive` feature. Each field becomes a header named after the
+/// field, and can be adjusted with the `ttygrid` attribute:
+///
+/// ```ignore
+/// #[derive(TtyGridRow)]
+/// struct Process {
+///     #[ttygrid(priority = 3, align = "right")]
+///     pid: u32,
+///     #[ttygrid(name = "command")]
+///     cmd: String,
+///     #[ttygrid(skip)]
+///     internal: bool,
+/// }
+///
+/// let mut grid = TTYGrid::new(Process::headers())?;
+/// grid.add_line(process.into_line(&grid)?);
+/// ```
+///
+/// The attribute accepts `name`, `key`, `priority`, `align` (`"left"`, `"right"`, `"center"`,
+/// `"decimal"` or `"unit"`) and `skip`. Fields are converted with [ToString].
+pub trait TtyGridRow {
+    /// Yield a new set of headers for the fields.
+    fn headers() -> Vec<SafeGridHeader>;
+
+    /// Yield the line for this value, bound to the headers of the grid.
+    #[allow(clippy::wrong_self_convention)]
+    fn into_line(&self, grid: &TTYGrid) -> Result<GridLine>;
+}
+
+/// GridConfig describes the headers, theme and layout of a [TTYGrid], without any of its
+/// content. With the `serde` feature enabled, it can be loaded from a configuration file and
+/// passed to [TTYGrid::from_config].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct GridConfig {
+    pub headers: Vec<GridHeader>,
+    pub theme: Theme,
+    pub layout: LayoutConfig,
+}
+
+/// LayoutConfig is the part of a [GridConfig] which decides which columns are displayed, and
+/// how. Columns are named by their keys (see [GridHeader::key]); naming a column the grid does
+/// not have is an error when the config is loaded. Pins are kept by the headers themselves (see
+/// [GridHeader::set_pin]).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct LayoutConfig {
+    /// Columns hidden with [TTYGrid::set_hidden].
+    pub hidden: Vec<String>,
+    /// Columns shown with [TTYGrid::set_hidden], which may be hidden by default.
+    pub shown: Vec<String>,
+    /// Columns frozen with [TTYGrid::set_frozen].
+    pub frozen: Vec<String>,
+    /// The views defined with [TTYGrid::define_view], by name.
+    pub views: Vec<(String, Vec<String>)>,
+    /// The view selected with [TTYGrid::set_active_view].
+    pub active_view: Option<String>,
+    /// The sort order set with [TTYGrid::sort_spec].
+    pub sort: Vec<(String, SortOrder)>,
+    pub overflow: OverflowPolicy,
+    /// The column set with [TTYGrid::set_key_column].
+    pub key_column: Option<String>,
+    pub table_align: TableAlign,
+}
+
 /// A collection of grid items. Usually instantiated by [crate::add_line!].
+///
+/// The items used to be the public field of the line (`line.0`), and now that the line carries
+/// its depth, colors and other settings they are private: use [GridLine::items] and
+/// [GridLine::items_mut] instead, and [GridLine::from] and [GridLine::into_items] to convert
+/// from and to a [Vec] of items.
+///
+/// ```
+/// use ttygrid::{header, GridItem, GridLine};
+///
+/// let name = header!("name");
+/// let mut line = GridLine::from(vec![GridItem::new(name.clone(), "web-1".to_string())]);
+/// line.items_mut()[0] = GridItem::new(name, "web-2".to_string());
+///
+/// assert_eq!(line.items()[0].contents(), "web-2");
+/// assert_eq!(line.into_items().len(), 1);
+/// ```
 #[derive(Clone, Default, Debug)]
-pub struct GridLine(pub Vec<GridItem>);
+pub struct GridLine {
+    items: Vec<GridItem>,
+    depth: usize,
+    colors: Option<Colors>,
+    hidden: bool,
+    indent: usize,
+    // the position of each item which failed its header's validator, with the error.
+    errors: Vec<(usize, String)>,
+    // whether this is the line standing in for those dropped to fit the memory limit.
+    placeholder: bool,
+}
+
+impl From<Vec<GridItem>> for GridLine {
+    fn from(items: Vec<GridItem>) -> Self {
+        Self {
+            items,
+            depth: 0,
+            colors: None,
+            hidden: false,
+            indent: 0,
+            errors: Vec::new(),
+            placeholder: false,
+        }
+    }
+}
+
+impl From<GridLine> for Vec<GridItem> {
+    fn from(line: GridLine) -> Self {
+        line.items
+    }
+}
 
 impl GridLine {
-    fn selected(&self, grid: &TTYGrid) -> Self {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&GridItem> {
+        self.items.get(idx)
+    }
+
+    pub fn push(&mut self, item: GridItem) {
+        self.items.push(item)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, GridItem> {
+        self.items.iter()
+    }
+
+    pub fn items(&self) -> &[GridItem] {
+        &self.items
+    }
+
+    pub fn items_mut(&mut self) -> &mut Vec<GridItem> {
+        &mut self.items
+    }
+
+    pub fn into_items(self) -> Vec<GridItem> {
+        self.items
+    }
+
+    /// Set the depth of this line within a tree; see [TTYGrid::set_tree_column]. Lines at depth 0
+    /// are the roots of the tree.
+    pub fn set_depth(&mut self, depth: usize) {
+        self.depth = depth
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Indent the contents of the first displayed column of this line by a number of levels, each
+    /// the indent string of the grid (see [TTYGrid::set_indent_string]), while the other columns
+    /// stay aligned. This is meant for nested listings such as dependency lists; use
+    /// [TTYGrid::set_tree_column] for tree guides instead.
+    ///
+    /// The width of the indentation is reserved in the first column which may be displayed, so
+    /// when that column is dropped to fit the terminal, the indentation may widen the next one.
+    ///
+    /// ```
+    /// use ttygrid::{grid, header, GridLine};
+    ///
+    /// let mut grid = grid!(header!("name"), header!("version")).unwrap();
+    /// let packages = [
+    ///     ("app", "1.0", 0),
+    ///     ("serde", "1.0.200", 1),
+    ///     ("serde_derive", "1.0.200", 2),
+    /// ];
+    /// for (name, version, indent) in packages {
+    ///     let mut line = grid.line_from(vec![name.to_string(), version.to_string()]).unwrap();
+    ///     line.set_indent(indent);
+    ///     grid.add_line(line);
+    /// }
+    ///
+    /// let output = grid.display().unwrap();
+    /// let lines = output.lines().skip(2).collect::<Vec<&str>>();
+    /// assert!(lines[2].starts_with("    serde_derive"));
+    /// assert_eq!(lines[0].find("1.0"), lines[2].find("1.0.200"));
+    /// ```
+    pub fn set_indent(&mut self, levels: usize) {
+        self.indent = levels
+    }
+
+    pub fn indent(&self) -> usize {
+        self.indent
+    }
+
+    /// Hide this line, or show it again; see [TTYGrid::set_line_hidden].
+    pub fn set_hidden(&mut self, hidden: bool) {
+        self.hidden = hidden
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// Set the colors of this line, which take precedence over the colors of the theme and the
+    /// column; see [Style]. They are only used by [TTYGrid::write].
+    pub fn set_colors(&mut self, colors: Colors) {
+        self.colors = Some(colors)
+    }
+
+    pub fn colors(&self) -> Option<Colors> {
+        self.colors
+    }
+
+    // moves the items to the headers at their positions, cutting the line to the headers or
+    // filling it with empty items.
+    fn rebind(&mut self, headers: &HeaderList) {
+        self.items.truncate(headers.len());
+        for (item, header) in self.items.iter_mut().zip(headers.iter()) {
+            item.header = header.clone();
+        }
+        for header in headers.iter().skip(self.items.len()) {
+            self.items
+                .push(GridItem::new(header.clone(), String::new()));
+        }
+    }
+
+    // applies the transforms of the headers to the items, then checks them with the validators
+    // of the headers.
+    fn transformed(mut self) -> Self {
+        self.errors.clear();
+
+        for (pos, item) in self.items.iter_mut().enumerate() {
+            if item.sparkline.is_some() || item.lazy.is_some() || item.raw.is_some() {
+                continue;
+            }
+
+            let (transform, validator) = {
+                let header = item.header.borrow();
+                (header.transform.clone(), header.validator.clone())
+            };
+
+            if let Some(transform) = transform {
+                item.contents = (transform.0)(&item.contents);
+            }
+
+            if let Some(validator) = validator {
+                if let Err(e) = (validator.0)(&item.contents) {
+                    self.errors.push((pos, e));
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Whether every item of this line passed the validator of its header, when it was added to
+    /// the grid; see [GridHeader::set_validator].
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    // yields the items of the selected headers, in the order they are selected.
+    fn selected(&self, selected: &HeaderList) -> Self {
         let mut ret = Vec::new();
-        for item in self.0.iter() {
-            if grid.is_selected(item.header.clone()) {
+        for header in selected.iter() {
+            if let Some(item) = self.items.iter().find(|i| Rc::ptr_eq(&i.header, header)) {
                 ret.push(item.clone())
             }
         }
 
-        GridLine(ret)
+        GridLine {
+            items: ret,
+            depth: self.depth,
+            colors: self.colors,
+            hidden: self.hidden,
+            indent: self.indent,
+            errors: Vec::new(),
+            placeholder: self.placeholder,
+        }
+    }
+}
+
+impl std::ops::Index<usize> for GridLine {
+    type Output = GridItem;
+
+    fn index(&self, idx: usize) -> &Self::Output {
+        &self.items[idx]
+    }
+}
+
+impl IntoIterator for GridLine {
+    type Item = GridItem;
+    type IntoIter = std::vec::IntoIter<GridItem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a GridLine {
+    type Item = &'a GridItem;
+    type IntoIter = std::slice::Iter<'a, GridItem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+impl FromIterator<GridItem> for GridLine {
+    fn from_iter<I: IntoIterator<Item = GridItem>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<GridItem>>())
     }
 }
 
 impl fmt::Display for GridLine {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        for contents in self.0.clone() {
+        for contents in self.items.clone() {
             write!(formatter, "{}", contents)?
         }
 
@@ -439,39 +5316,91 @@ impl fmt::Display for GridLine {
     }
 }
 
-#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
-struct LengthMapper(Vec<Vec<(SafeGridHeader, usize)>>);
+// LengthMapper tracks the longest item of each column of the measured lines.
+#[derive(Debug, Default)]
+struct LengthMapper {
+    // the header, its longest item, and the number of lines it was found in
+    columns: Vec<(SafeGridHeader, usize, usize)>,
+    lines: usize,
+    quantum: usize,
+    // the width of the terminal, which percentage widths are relative to
+    width: usize,
+}
 
 impl LengthMapper {
-    fn map_lines(&mut self, lines: Vec<GridLine>) {
-        for line in lines.clone() {
-            let len = self.0.len();
-            self.0.push(Vec::new()); // now len is equal to index
-            for item in line.0 {
-                self.0
-                    .get_mut(len)
-                    .unwrap()
-                    .push((item.header.clone(), item.len()));
+    // lens are the widths of the line's items, including any extra width the grid displays
+    // before them.
+    fn map_line(&mut self, line: &GridLine, lens: &[usize]) {
+        self.lines += 1;
+
+        for (item, len) in line.items.iter().zip(lens) {
+            let found = self
+                .columns
+                .iter()
+                .position(|(h, _, _)| Rc::ptr_eq(h, &item.header))
+                .or_else(|| {
+                    self.columns
+                        .iter()
+                        .position(|(h, _, _)| h.borrow().eq(&item.header.borrow()))
+                });
+
+            match found {
+                Some(idx) => {
+                    let column = &mut self.columns[idx];
+                    column.1 = column.1.max(*len);
+                    column.2 += 1;
+                }
+                None => self.columns.push((item.header.clone(), *len, 1)),
             }
         }
     }
 
+    // widens the header's column by the extra characters, if any of its items were measured.
+    fn reserve(&mut self, header: &SafeGridHeader, extra: usize) {
+        if let Some(column) = self
+            .columns
+            .iter_mut()
+            .find(|(h, _, _)| Rc::ptr_eq(h, header))
+        {
+            column.1 += extra;
+        }
+    }
+
     fn max_len_for_column(&self, header: &GridHeader) -> Result<usize> {
-        let mut max_len = 0;
-        for line in self.0.clone() {
-            let found = line.iter().find(|i| i.0.borrow().eq(header));
+        let max_len = self.unrounded_len_for_column(header)?;
+        match header.width {
+            Some(Width::Chars(_)) | Some(Width::Percent(_)) => Ok(max_len),
+            _ if self.quantum > 1 => Ok(max_len.div_ceil(self.quantum) * self.quantum),
+            _ => Ok(max_len),
+        }
+    }
+
+    // the width of the header's column before it is rounded up to the quantum.
+    fn unrounded_len_for_column(&self, header: &GridHeader) -> Result<usize> {
+        match header.width {
+            Some(Width::Chars(chars)) => return Ok(chars + header.gap()),
+            Some(Width::Percent(percent)) => return Ok(self.width * percent / 100),
+            _ => {}
+        }
+
+        let found = self
+            .columns
+            .iter()
+            .find(|(h, _, _)| std::ptr::eq(h.as_ptr(), header))
+            .or_else(|| self.columns.iter().find(|(h, _, _)| h.borrow().eq(header)));
 
-            if found.is_none() {
+        let max_len = match found {
+            Some((_, max_len, lines)) if *lines >= self.lines => *max_len,
+            None if self.lines == 0 => 0,
+            _ => {
                 return Err(anyhow!(
                     "panic: cannot find pre-existing column in line, report this bug"
-                ));
-            }
-
-            if max_len < found.unwrap().1 {
-                max_len = found.unwrap().1
+                ))
             }
-        }
+        };
 
+        // the minimum size is in characters, while lengths include the right padding
+        let max_len = max_len.max(header.min_size.map(|m| m + 1).unwrap_or_default());
         Ok(max_len + header.max_pad.unwrap_or(0) + 2)
     }
 
@@ -18,8 +18,30 @@
 ///    println!("{}", grid.display().unwrap());
 /// ```
 ///
+/// Headers whose number is only known at runtime are given as a collection following `..`:
+///
+/// ```
+///    use ttygrid::{grid, header, SafeGridHeader};
+///    let headers = (1..=3)
+///        .map(|n| {
+///            let text = format!("column {}", n);
+///            header!(text)
+///        })
+///        .collect::<Vec<SafeGridHeader>>();
+///
+///    let grid = grid!(..headers).unwrap();
+///    assert_eq!(grid.headers().len(), 3);
+/// ```
+///
+/// See also [crate::grid_from].
 #[macro_export]
 macro_rules! grid {
+    (.. $headers:expr) => {
+        {
+            use $crate::TTYGrid;
+            TTYGrid::new($headers.into_iter().collect())
+        }
+    };
     ($($header:expr),*) => {
         {
             use $crate::TTYGrid;
@@ -30,12 +52,26 @@ macro_rules! grid {
 
 /// header defines a [crate::SafeGridHeader] for use with the [crate::TTYGrid].
 ///
-/// It is variadic and composes of two current options:
+/// It is variadic and composes of three current options:
 ///
 /// - text by itself as the first position will yield a base header with the text set.
 /// - a second parameter, optionally provided, will set the priority to a [usize]. This controls
 ///   display capabilities where the terminal width is too small to display all columns. See
-///   [crate::grid!] for more.
+///   [crate::grid!] for more. A [crate::Priority] tier may be provided instead of a number, by
+///   its path.
+/// - instead of the priority, named options separated by commas, each setting a property of the
+///   [crate::GridHeader]:
+///   - `priority = n`: [crate::GridHeader::set_priority].
+///   - `tier = Priority::Important`: [crate::GridHeader::set_tier].
+///   - `key = "name"`: [crate::GridHeader::set_key].
+///   - `align = left`, `right`, `center`, `decimal` or `unit`: [crate::GridHeader::set_alignment].
+///   - `min = n`: [crate::GridHeader::set_min_size].
+///   - `max = n`: [crate::GridHeader::set_max_width].
+///   - `pad = n`: [crate::GridHeader::set_max_pad].
+///   - `pad_char = '.'`: [crate::GridHeader::set_pad_char].
+///   - `width = Width::Chars(n)`: [crate::GridHeader::set_width].
+///   - `hidden = true`: [crate::GridHeader::set_default_hidden].
+///   - `pin = Pin::Right`: [crate::GridHeader::set_pin].
 ///
 /// Examples:
 ///
@@ -51,6 +87,15 @@ macro_rules! grid {
 ///    let name = "foo";
 ///    let priority = 20;
 ///    assert_eq!(header!(name, priority), header!("foo", 20));
+///
+///    use ttygrid::Priority;
+///    assert!(header!("id", Priority::Essential).borrow().is_essential());
+///
+///    use ttygrid::Alignment;
+///    let size = header!("size", priority = 4, align = right, max = 12);
+///    assert_eq!(size.borrow().priority(), 4);
+///    assert_eq!(size.borrow().alignment(), Alignment::Right);
+///    assert_eq!(size, header!("size", align = right, max = 12, priority = 4,));
 /// ```
 #[macro_export]
 macro_rules! header {
@@ -71,32 +116,118 @@ macro_rules! header {
                 .set_priority($priority),
         ))
     }};
+
+    ($text:tt,$tier:path) => {{
+        use std::cell::RefCell;
+        use std::rc::Rc;
+        use $crate::GridHeader;
+        Rc::new(RefCell::new(
+            GridHeader::default().set_text($text).set_tier($tier),
+        ))
+    }};
+
+    ($text:tt, $option:ident = $($options:tt)+) => {{
+        use std::cell::RefCell;
+        use std::rc::Rc;
+        use $crate::GridHeader;
+        Rc::new(RefCell::new($crate::header!(
+            @options GridHeader::default().set_text($text), $option = $($options)+
+        )))
+    }};
+
+    // applies the named options to the header one at a time.
+    (@options $header:expr $(,)?) => { $header };
+    (@options $header:expr, align = $align:ident $(, $($rest:tt)*)?) => {
+        $crate::header!(
+            @options $header.set_alignment($crate::header!(@align $align)) $(, $($rest)*)?
+        )
+    };
+    (@options $header:expr, tier = $tier:path $(, $($rest:tt)*)?) => {
+        $crate::header!(@options $header.set_tier($tier) $(, $($rest)*)?)
+    };
+    (@options $header:expr, $option:ident = $value:expr $(, $($rest:tt)*)?) => {
+        $crate::header!(@options $crate::header!(@set $header, $option, $value) $(, $($rest)*)?)
+    };
+
+    (@set $header:expr, priority, $value:expr) => { $header.set_priority($value) };
+    (@set $header:expr, key, $value:expr) => { $header.set_key($value) };
+    (@set $header:expr, min, $value:expr) => { $header.set_min_size($value) };
+    (@set $header:expr, max, $value:expr) => { $header.set_max_width($value) };
+    (@set $header:expr, pad, $value:expr) => { $header.set_max_pad($value) };
+    (@set $header:expr, pad_char, $value:expr) => { $header.set_pad_char($value) };
+    (@set $header:expr, width, $value:expr) => { $header.set_width($value) };
+    (@set $header:expr, hidden, $value:expr) => { $header.set_default_hidden($value) };
+    (@set $header:expr, pin, $value:expr) => { $header.set_pin($value) };
+
+    (@align left) => { $crate::Alignment::Left };
+    (@align right) => { $crate::Alignment::Right };
+    (@align center) => { $crate::Alignment::Center };
+    (@align decimal) => { $crate::Alignment::Decimal };
+    (@align unit) => { $crate::Alignment::Unit };
 }
 
 /// add_line defines a [crate::GridLine] with [crate::GridItem]s attached.
 ///
 /// The first element provided is the grid; and the rest are strings which correspond to headers
 /// set to the grid, in order of appearance. A line **must** be equal to the number of headers,
-/// otherwise this macro will yield [anyhow::Error].
+/// otherwise this macro will panic. Grids in ragged mode relax this; see
+/// [crate::TTYGrid::set_ragged]. Use [crate::try_add_line!] to get an error instead.
 ///
 /// Please see the [crate::grid!] example for more.
 #[macro_export]
 macro_rules! add_line {
     ($grid:expr, $($content:expr),*) => {
-        {
-            use anyhow::anyhow;
-            use $crate::{GridLine, GridItem};
-            let content = vec![$($content),*];
+        if let Err(e) = $crate::try_add_line!($grid, $($content),*) {
+            panic!("add_line!: {}", e)
+        }
+    };
+}
 
-            if content.len() != $grid.headers().len() {
-                Err(anyhow!("ttygrid panic: content items must equal the number of headers"))
-            } else {
-                $grid.add_line(GridLine(content.iter().enumerate().map(|(i, item)| {
-                    GridItem::new($grid.headers().get(i).unwrap().clone(), item.to_string())
-                }).collect()));
+/// try_add_line is [crate::add_line!], but yields [anyhow::Error] if the number of items does
+/// not match the number of headers, instead of panicking.
+///
+/// ```
+///    use ttygrid::{grid, header, try_add_line};
+///    let mut grid = grid!(header!("one"), header!("two")).unwrap();
+///
+///    assert!(try_add_line!(grid, "1", "2").is_ok());
+///    assert!(try_add_line!(grid, "1").is_err());
+/// ```
+#[macro_export]
+macro_rules! try_add_line {
+    ($grid:expr, $($content:expr),*) => {
+        $grid.add_contents(vec![$($content.to_string()),*])
+    };
+}
 
-                Ok(())
-            }
+/// assert_grid_eq lays the grid out for a terminal of the width, 80 columns unless given, and
+/// panics with a line by line comparison if it is not displayed as expected; see
+/// [crate::test::compare_grid] for how the lines are compared. This is meant for the tests of
+/// programs which display grids.
+///
+/// ```
+///    use ttygrid::{add_line, assert_grid_eq, grid, header};
+///    let mut grid = grid!(header!("name"), header!("size")).unwrap();
+///    add_line!(grid, "web-1", 10);
+///
+///    assert_grid_eq!(
+///        grid,
+///        "
+///        name        size
+///        ----------------
+///        web-1       10
+///        ",
+///        width = 100
+///    );
+/// ```
+#[macro_export]
+macro_rules! assert_grid_eq {
+    ($grid:expr, $expected:expr $(,)?) => {
+        $crate::assert_grid_eq!($grid, $expected, width = 80)
+    };
+    ($grid:expr, $expected:expr, width = $width:expr $(,)?) => {
+        if let Err(e) = $crate::test::compare_grid(&mut $grid, $expected, $width) {
+            panic!("assert_grid_eq!: {}", e)
         }
     };
 }
@@ -0,0 +1,109 @@
+use crate::{SafeGridHeader, TTYGrid};
+use anyhow::Result;
+use crossterm::style::{Color, Colors};
+
+/// MarkerColumn turns a column into a narrow gutter of one or two character markers, like the
+/// `+`, `-`, `~` and `!` of diff and status listings, each written in its own colors. Marker
+/// columns are laid out without padding, and with the priority of [crate::Priority::Essential]
+/// columns, so other columns are dropped before them to fit the terminal; see
+/// [TTYGrid::set_marker_column]. The colors are only used by [TTYGrid::write].
+///
+/// By default `+` is green, `-` red, `~` yellow and `!` magenta.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MarkerColumn {
+    colors: Vec<(String, Colors)>,
+}
+
+impl Default for MarkerColumn {
+    fn default() -> Self {
+        let foreground = |color| Colors {
+            foreground: Some(color),
+            background: None,
+        };
+
+        Self {
+            colors: vec![
+                ("+".to_string(), foreground(Color::Green)),
+                ("-".to_string(), foreground(Color::Red)),
+                ("~".to_string(), foreground(Color::Yellow)),
+                ("!".to_string(), foreground(Color::Magenta)),
+            ],
+        }
+    }
+}
+
+impl MarkerColumn {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the colors of the marker, replacing any it had. Markers without colors are written in
+    /// the colors of their cell.
+    pub fn set_colors(mut self, marker: &str, colors: Colors) -> Self {
+        self.colors.retain(|(m, _)| m != marker);
+        self.colors.push((marker.to_string(), colors));
+        self
+    }
+
+    /// The colors of the marker, if it has any.
+    pub fn colors(&self, marker: &str) -> Option<Colors> {
+        self.colors
+            .iter()
+            .find(|(m, _)| m == marker.trim())
+            .map(|(_, colors)| *colors)
+    }
+}
+
+impl TTYGrid {
+    /// Display the header's column as a [MarkerColumn]. The header's padding is set to none, so
+    /// the column is only as wide as its markers and the space which separates columns. Unlike
+    /// [crate::Priority::Essential] columns, marker columns do not count towards the narrowest
+    /// layout (see [TTYGrid::minimum_width]); they are kept along with whichever columns fit.
+    ///
+    /// ```
+    /// use crossterm::style::{Color, Colored};
+    /// use ttygrid::{add_line, grid, header, test::FakeTerm, MarkerColumn, Priority};
+    ///
+    /// // colors are written even when NO_COLOR is set
+    /// Colored::set_ansi_color_disabled(false);
+    ///
+    /// let marker = header!("");
+    /// let mut term = FakeTerm::new(20, 10);
+    /// let mut grid = grid!(marker.clone(), header!("name"), header!("notes", Priority::Debug))
+    ///     .unwrap();
+    /// grid.refresh_width(&term).unwrap();
+    /// grid.set_marker_column(marker, MarkerColumn::new()).unwrap();
+    /// add_line!(grid, "+", "web-1", "new");
+    /// add_line!(grid, "-", "web-2", "gone");
+    ///
+    /// grid.write(&mut term).unwrap();
+    /// assert_eq!(term.lines()[2], "+   web-1");
+    /// assert_eq!(term.spans()[3][0].colors.foreground, Some(Color::Red));
+    /// ```
+    pub fn set_marker_column(
+        &mut self,
+        header: SafeGridHeader,
+        markers: MarkerColumn,
+    ) -> Result<()> {
+        let idx = self.header_index(&header)?;
+        header.borrow_mut().max_pad = Some(0);
+        self.markers.retain(|(i, _)| *i != idx);
+        self.markers.push((idx, markers));
+        Ok(())
+    }
+
+    /// Display the header's column like any other. Its padding is left as it is.
+    pub fn clear_marker_column(&mut self, header: SafeGridHeader) -> Result<()> {
+        let idx = self.header_index(&header)?;
+        self.markers.retain(|(i, _)| *i != idx);
+        Ok(())
+    }
+
+    // the marker column at the position, if it is one.
+    pub(crate) fn marker_column(&self, idx: usize) -> Option<&MarkerColumn> {
+        self.markers
+            .iter()
+            .find(|(i, _)| *i == idx)
+            .map(|(_, markers)| markers)
+    }
+}
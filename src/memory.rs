@@ -0,0 +1,137 @@
+use crate::{GridItem, GridLine, TTYGrid};
+use std::ops::Range;
+
+impl TTYGrid {
+    /// Limit the memory the lines of the grid take to about this many bytes, so accidentally huge
+    /// inputs do not exhaust the memory of the program. Once the lines exceed the limit, the first
+    /// lines which fit in half of it are kept, followed by a line reading "… N lines skipped" in
+    /// the first column, followed by the latest lines which fit in the rest; the lines between
+    /// them are dropped as more are added. The skipped line is a line of the grid like any other,
+    /// so it is sorted, exported and counted by [TTYGrid::lines] like the others, and positions of
+    /// lines added before the limit was reached may change. The size of a line is estimated from
+    /// the lengths of its contents. There is no limit by default.
+    ///
+    /// ```
+    /// use ttygrid::{grid, header};
+    ///
+    /// let mut grid = grid!(header!("name")).unwrap();
+    /// grid.set_memory_limit(Some(16 * 1024));
+    /// for i in 0..10_000 {
+    ///     grid.add_contents(vec![format!("line-{}", i)]).unwrap();
+    /// }
+    ///
+    /// assert!(grid.skipped_lines() > 9_000);
+    /// let output = grid.display().unwrap();
+    /// assert!(output.contains("line-0\n"));
+    /// assert!(output.contains(&format!("… {} lines skipped", grid.skipped_lines())));
+    /// assert!(output.ends_with("line-9999\n"));
+    /// ```
+    pub fn set_memory_limit(&mut self, bytes: Option<usize>) {
+        self.memory_limit = bytes;
+        self.enforce_memory_limit();
+    }
+
+    pub fn memory_limit(&self) -> Option<usize> {
+        self.memory_limit
+    }
+
+    /// The number of lines dropped to stay within the memory limit; see
+    /// [TTYGrid::set_memory_limit].
+    pub fn skipped_lines(&self) -> usize {
+        self.skipped
+    }
+
+    // accounts for the lines added at the positions, telling the observers about them before
+    // any are dropped to fit the memory limit.
+    pub(crate) fn lines_added(&mut self, positions: Range<usize>) {
+        self.notify_rows_added(positions.clone());
+        self.memory_used += self.lines[positions]
+            .iter()
+            .map(GridLine::memory_size)
+            .sum::<usize>();
+        self.enforce_memory_limit();
+    }
+
+    fn enforce_memory_limit(&mut self) {
+        let limit = match self.memory_limit {
+            Some(limit) if self.memory_used > limit => limit,
+            _ => return,
+        };
+
+        let marker = match self.find_skip_marker() {
+            Some(marker) => marker,
+            None => self.insert_skip_marker(limit / 2),
+        };
+
+        // lines are dropped in batches, so adding a line does not move the tail every time
+        let target = limit - limit / 8;
+        let mut end = marker + 1;
+        while self.memory_used > target && end + 1 < self.lines.len() {
+            self.memory_used = self
+                .memory_used
+                .saturating_sub(self.lines[end].memory_size());
+            end += 1;
+        }
+
+        self.skipped += end - (marker + 1);
+        self.lines.drain(marker + 1..end);
+        self.order.clear();
+
+        let text = format!("{} {} lines skipped", self.charset.ellipsis(), self.skipped);
+        if let Some(item) = self.lines[marker].items.first_mut() {
+            self.memory_used = self.memory_used + text.len() - item.contents.len();
+            item.contents = text;
+        }
+    }
+
+    // the position of the skipped line, which moves when lines are inserted or removed.
+    fn find_skip_marker(&mut self) -> Option<usize> {
+        if let Some(marker) = self.skip_marker {
+            if self.lines.get(marker).is_some_and(|line| line.placeholder) {
+                return Some(marker);
+            }
+        }
+
+        self.skip_marker = self.lines.iter().position(|line| line.placeholder);
+        self.skip_marker
+    }
+
+    // inserts the skipped line after the first lines which fit in the bytes, yielding its
+    // position.
+    fn insert_skip_marker(&mut self, bytes: usize) -> usize {
+        let mut used = 0;
+        let marker = self
+            .lines
+            .iter()
+            .take_while(|line| {
+                used += line.memory_size();
+                used <= bytes
+            })
+            .count();
+
+        let mut line = self
+            .headers
+            .iter()
+            .map(|header| GridItem::new(header.clone(), String::new()))
+            .collect::<GridLine>();
+        line.placeholder = true;
+
+        self.memory_used += line.memory_size();
+        self.lines.insert(marker, line);
+        self.order.clear();
+        self.skip_marker = Some(marker);
+        marker
+    }
+}
+
+impl GridLine {
+    // an estimate of the memory the line takes.
+    pub(crate) fn memory_size(&self) -> usize {
+        std::mem::size_of::<GridLine>()
+            + self
+                .items
+                .iter()
+                .map(|item| std::mem::size_of::<GridItem>() + item.contents.len())
+                .sum::<usize>()
+    }
+}
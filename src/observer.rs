@@ -0,0 +1,102 @@
+use crate::{GridLine, HeaderId, TTYGrid};
+use std::{
+    ops::Range,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+/// GridObserver is told about what a grid does, so instrumentation such as timing, metrics or
+/// logging of dropped columns can be attached to it; see [TTYGrid::add_observer]. Every method
+/// does nothing by default, so observers implement only the ones they need. Observers are shared
+/// by clones of the grid, and take `&self`, so those which keep counts need interior
+/// mutability.
+///
+/// ```
+/// use std::{cell::Cell, rc::Rc};
+/// use ttygrid::{add_line, grid, header, GridLine, GridObserver};
+///
+/// #[derive(Default)]
+/// struct Counter {
+///     rows: Cell<usize>,
+///     renders: Cell<usize>,
+/// }
+///
+/// impl GridObserver for Counter {
+///     fn on_row_added(&self, _idx: usize, _line: &GridLine) {
+///         self.rows.set(self.rows.get() + 1)
+///     }
+///
+///     fn on_render_complete(&self, _elapsed: std::time::Duration) {
+///         self.renders.set(self.renders.get() + 1)
+///     }
+/// }
+///
+/// let counter = Rc::new(Counter::default());
+/// let mut grid = grid!(header!("name")).unwrap();
+/// grid.add_observer(counter.clone());
+/// add_line!(grid, "web-1");
+/// add_line!(grid, "web-2");
+/// grid.display().unwrap();
+///
+/// assert_eq!(counter.rows.get(), 2);
+/// assert_eq!(counter.renders.get(), 1);
+/// ```
+pub trait GridObserver {
+    /// A line was added to the grid at the position, such as with [TTYGrid::add_line] or
+    /// [TTYGrid::insert_line].
+    fn on_row_added(&self, _idx: usize, _line: &GridLine) {}
+
+    /// The columns were laid out for the width of the terminal, yielding the columns displayed
+    /// and those which were dropped to fit the terminal, both in display order. Columns stacked
+    /// into further tables (see [crate::OverflowPolicy::Stack]) are among the dropped ones.
+    fn on_layout_computed(&self, _width: usize, _selected: &[HeaderId], _dropped: &[HeaderId]) {}
+
+    /// The grid was rendered by [TTYGrid::display], [TTYGrid::write], [TTYGrid::write_plain] or
+    /// a similar method, which took the time given, including the layout.
+    fn on_render_complete(&self, _elapsed: Duration) {}
+}
+
+impl TTYGrid {
+    /// Attach the observer to the grid; see [GridObserver].
+    pub fn add_observer(&mut self, observer: Rc<dyn GridObserver>) {
+        self.observers.push(observer)
+    }
+
+    /// Detach every observer from the grid.
+    pub fn clear_observers(&mut self) {
+        self.observers.clear()
+    }
+
+    // tells the observers about the lines added at the positions.
+    pub(crate) fn notify_rows_added(&self, positions: Range<usize>) {
+        for observer in self.observers.iter() {
+            for idx in positions.clone() {
+                observer.on_row_added(idx, &self.lines[idx])
+            }
+        }
+    }
+
+    pub(crate) fn notify_layout_computed(&self) {
+        if self.observers.is_empty() {
+            return;
+        }
+
+        let selected = self
+            .selected
+            .iter()
+            .filter_map(|h| self.header_index(h).ok())
+            .map(|idx| self.header_id(idx))
+            .collect::<Vec<HeaderId>>();
+        let dropped = self.dropped_headers();
+
+        for observer in self.observers.iter() {
+            observer.on_layout_computed(self.width, &selected, &dropped)
+        }
+    }
+
+    pub(crate) fn notify_render_complete(&self, start: Instant) {
+        for observer in self.observers.iter() {
+            observer.on_render_complete(start.elapsed())
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{Alignment, GridLine, TTYGrid, TableAlign};
+use anyhow::Result;
+use std::{io::Write, rc::Rc};
+
+impl TTYGrid {
+    /// Write the grid to the writer without any terminal styling, exactly like [TTYGrid::display]
+    /// but without building the output as strings. Tables of plain ASCII text, which are not
+    /// wrapped and use no per-column display options such as deduplication, trees, sparklines,
+    /// redaction, decimal alignment or fixed widths, are rendered straight into a byte buffer
+    /// which is kept by the grid and reused by later calls; other tables are rendered like
+    /// [TTYGrid::display]. This is meant for tools which print thousands of lines a second.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header};
+    ///
+    /// let mut grid = grid!(header!("name"), header!("size")).unwrap();
+    /// add_line!(grid, "a", 10);
+    /// add_line!(grid, "bb", 200);
+    ///
+    /// let mut output = Vec::new();
+    /// grid.write_plain(&mut output).unwrap();
+    /// assert_eq!(String::from_utf8(output).unwrap(), grid.display().unwrap());
+    /// ```
+    pub fn write_plain(&mut self, mut writer: impl Write) -> Result<()> {
+        let start = std::time::Instant::now();
+        self.determine_headers()?;
+
+        let mut buf = std::mem::take(&mut self.plain_buffer);
+        buf.clear();
+
+        let fast = self.plain_eligible() && {
+            let selected = self.selected.clone();
+            let mut ok = true;
+            for (idx, layout) in self.layouts().into_iter().enumerate() {
+                if idx > 0 {
+                    buf.push(b'\n');
+                }
+
+                self.selected = layout;
+                if !self.render_plain(&mut buf) {
+                    ok = false;
+                    break;
+                }
+            }
+            self.selected = selected;
+            ok
+        };
+
+        if fast {
+            for line in self.legend_lines().into_iter().chain(self.status_line()) {
+                buf.extend_from_slice(line.as_bytes());
+                buf.push(b'\n');
+            }
+        }
+
+        let result = if fast {
+            writer.write_all(&buf)
+        } else {
+            self.display()
+                .and_then(|output| Ok(writer.write_all(output.as_bytes())?))
+                .map_err(std::io::Error::other)
+        };
+
+        self.plain_buffer = buf;
+        result?;
+        writer.flush()?;
+
+        // tables rendered like display() have told the observers already
+        if fast {
+            self.notify_render_complete(start);
+        }
+        Ok(())
+    }
+
+    // whether the display options of the grid allow the plain fast path. Options of the items are
+    // checked while rendering.
+    fn plain_eligible(&self) -> bool {
+        !self.wrap
+            && self.table_align == TableAlign::Left
+            && self.row_renderer.is_none()
+            && self.dedup.is_empty()
+            && self.tree_column.is_none()
+            && self.redactions.is_empty()
+            && self.headers.iter().all(|h| {
+                let h = h.borrow();
+                h.width.is_none()
+                    && h.pad_char == ' '
+                    && !matches!(h.alignment, Alignment::Decimal | Alignment::Unit)
+            })
+    }
+
+    // renders the selected headers into the buffer, yielding false if an item cannot be rendered
+    // on the fast path.
+    fn render_plain(&self, buf: &mut Vec<u8>) -> bool {
+        let mut header = Vec::new();
+        if self.show_header {
+            let line = self.header_line();
+            if !line.is_ascii() {
+                return false;
+            }
+
+            header.extend_from_slice(line.as_bytes());
+            header.push(b'\n');
+            pad(&mut header, b'-', self.delimiter_width());
+            header.push(b'\n');
+        }
+        buf.extend_from_slice(&header);
+
+        let lines = self.visible_lines().into_iter().map(|idx| &self.lines[idx]);
+        for (idx, line) in lines.enumerate() {
+            if self.repeats_header_before(idx) {
+                buf.extend_from_slice(&header);
+            }
+
+            if !self.render_plain_line(buf, line) {
+                return false;
+            }
+            buf.push(b'\n');
+        }
+
+        true
+    }
+
+    fn render_plain_line(&self, buf: &mut Vec<u8>, line: &GridLine) -> bool {
+        if line.indent > 0 {
+            return false;
+        }
+
+        let mut last = None;
+
+        let items = self
+            .selected
+            .iter()
+            .filter_map(|h| line.items.iter().find(|item| Rc::ptr_eq(h, &item.header)));
+
+        for item in items {
+            if item.sparkline.is_some() || item.raw.is_some() || !item.contents.is_ascii() {
+                return false;
+            }
+
+            let header = item.header.borrow();
+            let text = item.contents.as_bytes();
+            let width = item.max_len.unwrap_or(item.len());
+            let content = width.saturating_sub(header.gap());
+            let fill = content.saturating_sub(text.len());
+
+            last = Some(buf.len());
+            match header.alignment {
+                Alignment::Left => buf.extend_from_slice(text),
+                Alignment::Right | Alignment::Decimal | Alignment::Unit => {
+                    pad(buf, b' ', fill);
+                    buf.extend_from_slice(text)
+                }
+                Alignment::Center => {
+                    pad(buf, b' ', fill / 2);
+                    buf.extend_from_slice(text);
+                    pad(buf, b' ', fill - fill / 2)
+                }
+            }
+
+            let written = buf.len() - last.unwrap_or_default();
+            pad(buf, b' ', width.saturating_sub(written));
+        }
+
+        if let (true, Some(start)) = (self.trim_trailing_whitespace, last) {
+            while buf.len() > start
+                && matches!(
+                    buf.last(),
+                    Some(b' ' | b'\t' | b'\n' | b'\x0b' | b'\x0c' | b'\r')
+                )
+            {
+                buf.pop();
+            }
+        }
+
+        true
+    }
+}
+
+fn pad(buf: &mut Vec<u8>, byte: u8, n: usize) {
+    buf.resize(buf.len() + n, byte)
+}
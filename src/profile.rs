@@ -0,0 +1,270 @@
+use crate::{theme::basic, Charset, TTYGrid};
+use crossterm::style::{Color, Colors};
+
+/// ColorDepth is how many colors a terminal can display. Colors a grid is given which the
+/// terminal can not display are written as the closest color it can; see
+/// [TTYGrid::set_terminal_profile].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum ColorDepth {
+    /// No colors at all, such as for dumb terminals or when `NO_COLOR` is set.
+    None,
+    /// The 16 colors of the ANSI palette.
+    Basic,
+    /// The 256 colors of the xterm palette.
+    Ansi256,
+    /// Any RGB color. This is the default, which writes colors as they are given.
+    #[default]
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// The closest color to the color which can be displayed with this depth.
+    pub fn downgrade(&self, color: Color) -> Color {
+        match (self, color) {
+            (ColorDepth::None, _) => Color::Reset,
+            (ColorDepth::TrueColor, color) => color,
+            (ColorDepth::Ansi256, Color::Rgb { r, g, b }) => Color::AnsiValue(ansi256(r, g, b)),
+            (ColorDepth::Basic, Color::Rgb { r, g, b }) => nearest_basic((r, g, b)),
+            (ColorDepth::Basic, Color::AnsiValue(value)) => nearest_basic(rgb(value)),
+            (_, color) => color,
+        }
+    }
+
+    fn downgrade_colors(&self, colors: Colors) -> Colors {
+        Colors {
+            foreground: colors.foreground.map(|c| self.downgrade(c)),
+            background: colors.background.map(|c| self.downgrade(c)),
+        }
+    }
+}
+
+/// TerminalProfile describes what a terminal can display, so a grid can adjust its output to it;
+/// see [TerminalProfiles] for detecting the terminal a program runs in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct TerminalProfile {
+    pub charset: Charset,
+    pub color_depth: ColorDepth,
+    /// Whether the terminal displays OSC 8 hyperlinks. Grids do not write hyperlinks themselves;
+    /// this is for programs which put them in their items, such as with [crate::GridItem::raw].
+    pub hyperlinks: bool,
+}
+
+impl TerminalProfile {
+    pub fn new(charset: Charset, color_depth: ColorDepth, hyperlinks: bool) -> Self {
+        Self {
+            charset,
+            color_depth,
+            hyperlinks,
+        }
+    }
+}
+
+/// TerminalProfiles is a table of [TerminalProfile]s, keyed by the name of a terminal program
+/// (as in `$TERM_PROGRAM`, such as `iTerm.app`) or a terminal type (as in `$TERM`, such as
+/// `tmux`, which also matches `tmux-256color`). The default table knows tmux, screen, iTerm2,
+/// Apple's Terminal, WezTerm, VS Code, Windows Terminal, xterm, the linux console and dumb
+/// terminals; programs can register profiles of their own, which take precedence.
+///
+/// ```
+/// use ttygrid::{Charset, ColorDepth, TerminalProfile, TerminalProfiles};
+///
+/// let mut profiles = TerminalProfiles::default();
+/// assert_eq!(profiles.lookup("linux", None).charset, Charset::Ascii);
+/// assert_eq!(
+///     profiles.lookup("xterm-256color", None).color_depth,
+///     ColorDepth::Ansi256
+/// );
+///
+/// profiles.register(
+///     "my-term",
+///     TerminalProfile::new(Charset::Unicode, ColorDepth::Basic, true),
+/// );
+/// assert!(profiles.lookup("xterm", Some("my-term")).hyperlinks);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TerminalProfiles {
+    profiles: Vec<(String, TerminalProfile)>,
+}
+
+impl Default for TerminalProfiles {
+    fn default() -> Self {
+        use Charset::{Ascii, Unicode};
+        use ColorDepth::{Ansi256, Basic, TrueColor};
+
+        let profiles = [
+            ("dumb", Ascii, ColorDepth::None, false),
+            ("linux", Ascii, Basic, false),
+            ("xterm", Unicode, Basic, false),
+            ("screen", Unicode, Basic, false),
+            ("tmux", Unicode, Ansi256, false),
+            ("Apple_Terminal", Unicode, Ansi256, false),
+            ("iTerm.app", Unicode, TrueColor, true),
+            ("WezTerm", Unicode, TrueColor, true),
+            ("vscode", Unicode, TrueColor, true),
+            ("WindowsTerminal", Unicode, TrueColor, true),
+        ];
+
+        Self {
+            profiles: profiles
+                .iter()
+                .map(|(name, charset, depth, links)| {
+                    (
+                        name.to_string(),
+                        TerminalProfile::new(*charset, *depth, *links),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TerminalProfiles {
+    /// Register the profile of the terminal program or type, replacing any it had.
+    pub fn register(&mut self, name: &str, profile: TerminalProfile) {
+        self.profiles.retain(|(n, _)| n != name);
+        self.profiles.push((name.to_string(), profile));
+    }
+
+    /// Yield the profile of the terminal type and program. The program's profile is preferred
+    /// to the type's, as multiplexers and emulators often report a generic type. Types ending
+    /// in `256color` have at least 256 colors. Unknown terminals have the default profile,
+    /// which displays everything.
+    pub fn lookup(&self, term: &str, program: Option<&str>) -> TerminalProfile {
+        let find = |name: &str| {
+            self.profiles
+                .iter()
+                .rev()
+                .find(|(n, _)| {
+                    name == n
+                        || name
+                            .strip_prefix(n.as_str())
+                            .is_some_and(|rest| rest.starts_with('-'))
+                })
+                .map(|(_, profile)| *profile)
+        };
+
+        let mut profile = program
+            .and_then(find)
+            .or_else(|| find(term))
+            .unwrap_or_default();
+
+        if term.ends_with("256color") {
+            profile.color_depth = profile.color_depth.max(ColorDepth::Ansi256);
+        }
+
+        profile
+    }
+
+    /// Yield the profile of the terminal the program runs in, from `$TERM` and `$TERM_PROGRAM`;
+    /// Windows Terminal, which sets neither, is recognized by `$WT_SESSION`. `$COLORTERM` set to
+    /// `truecolor` or `24bit` raises the color depth, and `$NO_COLOR` removes the colors.
+    pub fn detect(&self) -> TerminalProfile {
+        let var = |name| std::env::var(name).ok();
+
+        let program = var("TERM_PROGRAM")
+            .or_else(|| std::env::var_os("WT_SESSION").map(|_| "WindowsTerminal".to_string()));
+        let mut profile = self.lookup(&var("TERM").unwrap_or_default(), program.as_deref());
+
+        if matches!(var("COLORTERM").as_deref(), Some("truecolor" | "24bit")) {
+            profile.color_depth = profile.color_depth.max(ColorDepth::TrueColor);
+        }
+
+        if std::env::var_os("NO_COLOR").is_some() {
+            profile.color_depth = ColorDepth::None;
+        }
+
+        profile
+    }
+}
+
+impl TTYGrid {
+    /// Adjust the output of the grid to the terminal profile: the charset is set to the
+    /// profile's (see [TTYGrid::set_charset]), and [TTYGrid::write], the [crate::Pager] and
+    /// similar methods write colors the terminal can not display as the closest ones it can.
+    ///
+    /// ```
+    /// use crossterm::style::{Color, Colored, Colors};
+    /// use ttygrid::{add_line, grid, header, test::FakeTerm, Charset, ColorDepth, TerminalProfile};
+    ///
+    /// // colors are written even when NO_COLOR is set
+    /// Colored::set_ansi_color_disabled(false);
+    ///
+    /// let mut term = FakeTerm::new(20, 5);
+    /// let mut grid = grid!(header!("name")).unwrap();
+    /// grid.refresh_width(&term).unwrap();
+    /// grid.set_header_color(Colors::new(Color::Rgb { r: 250, g: 10, b: 10 }, Color::Reset));
+    /// grid.set_terminal_profile(TerminalProfile::new(Charset::Ascii, ColorDepth::Basic, false));
+    /// add_line!(grid, "web-1");
+    ///
+    /// grid.write(&mut term).unwrap();
+    /// assert_eq!(grid.charset(), Charset::Ascii);
+    /// assert_eq!(term.spans()[0][0].colors.foreground, Some(Color::Red));
+    /// ```
+    pub fn set_terminal_profile(&mut self, profile: TerminalProfile) {
+        self.charset = profile.charset;
+        self.color_depth = profile.color_depth;
+    }
+
+    // the colors as the terminal can display them.
+    pub(crate) fn displayable(&self, colors: Colors) -> Colors {
+        self.color_depth.downgrade_colors(colors)
+    }
+}
+
+// the RGB value of a color of the xterm palette.
+fn rgb(value: u8) -> (u8, u8, u8) {
+    const BASIC: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (128, 0, 0),
+        (0, 128, 0),
+        (128, 128, 0),
+        (0, 0, 128),
+        (128, 0, 128),
+        (0, 128, 128),
+        (192, 192, 192),
+        (128, 128, 128),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (0, 0, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+
+    match value {
+        0..=15 => BASIC[value as usize],
+        16..=231 => {
+            let n = value - 16;
+            (level(n / 36), level(n / 6 % 6), level(n % 6))
+        }
+        _ => {
+            let grey = 8 + (value - 232) * 10;
+            (grey, grey, grey)
+        }
+    }
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+fn nearest_basic(color: (u8, u8, u8)) -> Color {
+    let code = (0..16)
+        .min_by_key(|n| distance(rgb(*n), color))
+        .unwrap_or(0);
+    basic(code)
+}
+
+// the closest color of the xterm palette's color cube and grey ramp to the RGB color.
+fn ansi256(r: u8, g: u8, b: u8) -> u8 {
+    (16..=255)
+        .min_by_key(|n| distance(rgb(*n), (r, g, b)))
+        .unwrap_or(16)
+}
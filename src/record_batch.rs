@@ -0,0 +1,78 @@
+use crate::{Alignment, GridHeader, GridItem, GridLine, SafeGridHeader, TTYGrid};
+use anyhow::Result;
+use arrow_array::RecordBatch;
+use arrow_cast::display::{ArrayFormatter, FormatOptions};
+use arrow_schema::DataType;
+use std::{borrow::Cow, cell::RefCell, rc::Rc};
+
+impl TTYGrid {
+    /// Create a grid from an Arrow record batch, with a header for each of its columns, named
+    /// after the field. Values are formatted like Arrow displays them, and nulls are empty.
+    /// Integer columns are aligned to the right and floating point and decimal columns on the
+    /// decimal point; see [Alignment]. Requires the `arrow` feature.
+    ///
+    /// ```
+    /// use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray};
+    /// use std::sync::Arc;
+    /// use ttygrid::{Alignment, TTYGrid};
+    ///
+    /// let batch = RecordBatch::try_from_iter(vec![
+    ///     ("name", Arc::new(StringArray::from(vec![Some("a"), None])) as ArrayRef),
+    ///     ("load", Arc::new(Float64Array::from(vec![0.25, 12.5])) as ArrayRef),
+    /// ])
+    /// .unwrap();
+    ///
+    /// let mut grid = TTYGrid::from_record_batch(&batch).unwrap();
+    /// assert_eq!(grid.column_names(), vec!["name", "load"]);
+    ///
+    /// let output = grid.display().unwrap();
+    /// let lines = output.lines().collect::<Vec<&str>>();
+    /// assert!(lines[2].starts_with("a") && lines[2].ends_with(" 0.25"));
+    /// assert!(lines[3].ends_with("12.5"));
+    /// ```
+    pub fn from_record_batch(batch: &RecordBatch) -> Result<Self> {
+        let schema = batch.schema();
+        let headers = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                Rc::new(RefCell::new(GridHeader {
+                    text: Cow::Owned(field.name().clone()),
+                    alignment: alignment(field.data_type()),
+                    ..Default::default()
+                }))
+            })
+            .collect::<Vec<SafeGridHeader>>();
+
+        let options = FormatOptions::default();
+        let formatters = batch
+            .columns()
+            .iter()
+            .map(|column| ArrayFormatter::try_new(column.as_ref(), &options))
+            .collect::<Result<Vec<ArrayFormatter>, _>>()?;
+
+        let mut grid = Self::new(headers.clone())?;
+        for row in 0..batch.num_rows() {
+            grid.add_line(
+                headers
+                    .iter()
+                    .zip(formatters.iter())
+                    .map(|(header, formatter)| {
+                        GridItem::new(header.clone(), formatter.value(row).to_string())
+                    })
+                    .collect::<GridLine>(),
+            );
+        }
+
+        Ok(grid)
+    }
+}
+
+fn alignment(data_type: &DataType) -> Alignment {
+    match data_type {
+        t if t.is_integer() => Alignment::Right,
+        t if t.is_floating() => Alignment::Decimal,
+        DataType::Decimal128(_, _) | DataType::Decimal256(_, _) => Alignment::Decimal,
+        _ => Alignment::Left,
+    }
+}
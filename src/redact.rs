@@ -0,0 +1,113 @@
+use crate::{SafeGridHeader, TTYGrid};
+use anyhow::Result;
+use std::{borrow::Cow, rc::Rc};
+
+/// RedactionPolicy controls how the contents of a column holding secrets, such as tokens or
+/// passwords, are displayed and exported; see [TTYGrid::set_redaction]. Empty items stay empty.
+///
+/// ```
+/// use ttygrid::RedactionPolicy;
+///
+/// assert_eq!(RedactionPolicy::Mask.apply("hunter2"), "********");
+/// assert_eq!(RedactionPolicy::LastN(4).apply("ghp_abcd1234"), "****1234");
+/// assert_eq!(RedactionPolicy::LastN(4).apply("1234"), "********");
+/// assert_eq!(
+///     RedactionPolicy::Hash.apply("hunter2"),
+///     RedactionPolicy::Hash.apply("hunter2")
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum RedactionPolicy {
+    /// Replace the contents with a mask of a fixed length, so the length of the secret is not
+    /// revealed either.
+    Mask,
+    /// Mask all but the last N characters. Contents which are not longer than N are masked
+    /// entirely.
+    LastN(usize),
+    /// Replace the contents with a short hash, so equal secrets can be told apart from different
+    /// ones without being revealed. The hash is not cryptographic: short or guessable secrets
+    /// can be recovered from it.
+    Hash,
+}
+
+const MASK: &str = "********";
+
+impl RedactionPolicy {
+    /// Redact the contents according to the policy.
+    pub fn apply(&self, contents: &str) -> String {
+        if contents.is_empty() {
+            return String::new();
+        }
+
+        match self {
+            RedactionPolicy::Mask => MASK.to_string(),
+            RedactionPolicy::LastN(n) => {
+                let len = contents.chars().count();
+                if len <= *n {
+                    MASK.to_string()
+                } else {
+                    MASK[..4].to_string() + &contents.chars().skip(len - n).collect::<String>()
+                }
+            }
+            RedactionPolicy::Hash => format!("{:08x}", fnv1a(contents)),
+        }
+    }
+}
+
+impl TTYGrid {
+    /// Redact the items of the header's column with the policy whenever they are displayed,
+    /// written or exported. The contents stored in the grid are not changed, so sorting and
+    /// change tracking still see the secrets.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, ExportFormat, RedactionPolicy};
+    ///
+    /// let token = header!("token");
+    /// let mut grid = grid!(header!("name"), token.clone()).unwrap();
+    /// add_line!(grid, "ci", "ghp_abcd1234");
+    /// grid.set_redaction(token, RedactionPolicy::LastN(4)).unwrap();
+    ///
+    /// assert!(grid.display().unwrap().contains("****1234"));
+    /// assert!(!grid.display().unwrap().contains("ghp_"));
+    ///
+    /// let mut csv = Vec::new();
+    /// grid.export(&mut csv, ExportFormat::Csv).unwrap();
+    /// assert_eq!(String::from_utf8(csv).unwrap(), "name,token\nci,****1234\n");
+    /// ```
+    pub fn set_redaction(&mut self, header: SafeGridHeader, policy: RedactionPolicy) -> Result<()> {
+        let idx = self.header_index(&header)?;
+        self.redactions.retain(|(i, _)| *i != idx);
+        self.redactions.push((idx, policy));
+        Ok(())
+    }
+
+    /// Stop redacting the header's column.
+    pub fn clear_redaction(&mut self, header: SafeGridHeader) -> Result<()> {
+        let idx = self.header_index(&header)?;
+        self.redactions.retain(|(i, _)| *i != idx);
+        Ok(())
+    }
+
+    // yields the contents as they may be shown for an item of the header.
+    pub(crate) fn redacted<'a>(&self, header: &SafeGridHeader, contents: &'a str) -> Cow<'a, str> {
+        let policy = self
+            .redactions
+            .iter()
+            .find(|(idx, _)| Rc::ptr_eq(&self.headers.0[*idx], header))
+            .map(|(_, policy)| policy);
+
+        match policy {
+            Some(policy) => Cow::Owned(policy.apply(contents)),
+            None => Cow::Borrowed(contents),
+        }
+    }
+}
+
+// 32-bit FNV-1a, which is stable across platforms and releases, unlike the std hashers.
+fn fnv1a(text: &str) -> u32 {
+    text.bytes().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+    })
+}
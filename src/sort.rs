@@ -0,0 +1,253 @@
+use crate::{leading_number, GridLine, HeaderList, SafeGridHeader, TTYGrid};
+use anyhow::Result;
+use std::{cmp::Ordering, rc::Rc};
+
+/// SortOrder is the direction a column is sorted in; see [TTYGrid::sort_spec].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+pub(crate) type Comparator = Rc<dyn Fn(&str, &str) -> Ordering>;
+
+impl TTYGrid {
+    /// Sort the lines by the columns, named by their keys (see [TTYGrid::column_names]), in
+    /// order of precedence: lines which are equal in the first column are ordered by the second,
+    /// and so on. The sort is stable, and is applied every time the grid is displayed, so lines
+    /// added later are sorted too. Columns are compared as numbers when both items start with
+    /// one, and as text otherwise, unless a comparator is set with [TTYGrid::set_comparator].
+    ///
+    /// Sorting only changes the order the lines are displayed and exported in: their positions,
+    /// as used by [TTYGrid::lines] and [TTYGrid::remove_line], do not change. Tree columns are
+    /// drawn from the positions, so sorting should not be combined with them.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, SortOrder};
+    ///
+    /// let mut grid = grid!(header!("status"), header!("name"), header!("mem")).unwrap();
+    /// add_line!(grid, "up", "b", "9MB");
+    /// add_line!(grid, "down", "a", "10MB");
+    /// add_line!(grid, "up", "a", "100MB");
+    ///
+    /// grid.sort_spec([("status", SortOrder::Desc), ("name", SortOrder::Asc)]).unwrap();
+    /// let output = grid.display().unwrap();
+    /// let names = output
+    ///     .lines()
+    ///     .skip(2)
+    ///     .filter_map(|l| l.split_whitespace().nth(1))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(names, vec!["a", "b", "a"]);
+    ///
+    /// grid.sort_spec([("mem", SortOrder::Asc)]).unwrap();
+    /// let output = grid.display().unwrap();
+    /// assert!(output.lines().nth(2).unwrap().ends_with("9MB"));
+    /// ```
+    pub fn sort_spec<'a>(
+        &mut self,
+        spec: impl IntoIterator<Item = (&'a str, SortOrder)>,
+    ) -> Result<()> {
+        let mut sort = Vec::new();
+
+        for (name, order) in spec {
+            sort.push((self.column_index(name)?, order));
+        }
+
+        self.sort = sort;
+        Ok(())
+    }
+
+    /// Stop sorting the lines. Lines are displayed in the order they were added again.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, SortOrder};
+    ///
+    /// let mut grid = grid!(header!("name")).unwrap();
+    /// for name in ["pear", "apple", "fig"] {
+    ///     add_line!(grid, name);
+    /// }
+    ///
+    /// let names = |grid: &mut ttygrid::TTYGrid| {
+    ///     let output = grid.display().unwrap();
+    ///     output.lines().skip(2).map(|l| l.trim().to_string()).collect::<Vec<_>>()
+    /// };
+    ///
+    /// grid.sort_spec([("name", SortOrder::Asc)]).unwrap();
+    /// assert_eq!(names(&mut grid), vec!["apple", "fig", "pear"]);
+    ///
+    /// grid.clear_sort();
+    /// assert_eq!(names(&mut grid), vec!["pear", "apple", "fig"]);
+    /// ```
+    pub fn clear_sort(&mut self) {
+        self.sort.clear();
+        self.order.clear();
+    }
+
+    /// Mark the labels of the columns the lines are sorted by with an arrow pointing up for
+    /// ascending and down for descending order (see [crate::Charset::sort_arrows]). The columns
+    /// are widened to fit the arrows. The [crate::Pager] always marks them.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, SortOrder};
+    ///
+    /// let mut grid = grid!(header!("name"), header!("size")).unwrap();
+    /// add_line!(grid, "web-1", 10);
+    /// grid.sort_spec([("size", SortOrder::Desc)]).unwrap();
+    /// grid.set_sort_indicator(true);
+    ///
+    /// assert!(grid.display().unwrap().starts_with("name        size ▼"));
+    ///
+    /// // the indicator is measured, so it never takes the place of the label
+    /// let mut grid = grid!(header!("id", pad = 0), header!("name")).unwrap();
+    /// add_line!(grid, 2, "web-2");
+    /// add_line!(grid, 1, "web-1");
+    /// grid.sort_spec([("id", SortOrder::Asc)]).unwrap();
+    /// grid.set_sort_indicator(true);
+    ///
+    /// assert!(grid.display().unwrap().starts_with("id ▲  name"));
+    /// ```
+    pub fn set_sort_indicator(&mut self, show: bool) {
+        self.sort_indicator = show
+    }
+
+    pub fn sort_indicator(&self) -> bool {
+        self.sort_indicator
+    }
+
+    // the sort indicator following the label of the header, if any.
+    pub(crate) fn sort_mark(&self, header: &SafeGridHeader) -> String {
+        let order = self
+            .sort
+            .iter()
+            .find(|(idx, _)| Rc::ptr_eq(&self.headers.0[*idx], header))
+            .map(|(_, order)| *order);
+
+        let (asc, desc) = self.charset.sort_arrows();
+        match order {
+            Some(SortOrder::Asc) if self.sort_indicator => format!(" {}", asc),
+            Some(SortOrder::Desc) if self.sort_indicator => format!(" {}", desc),
+            _ => String::new(),
+        }
+    }
+
+    /// Compare the items of the header's column with the comparator when sorting, instead of
+    /// comparing them as numbers or text.
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, SortOrder};
+    ///
+    /// let version = header!("version");
+    /// let mut grid = grid!(version.clone()).unwrap();
+    /// for v in ["1.10", "1.9", "1.2"] {
+    ///     add_line!(grid, v);
+    /// }
+    ///
+    /// // as numbers, 1.10 is smaller than 1.2
+    /// grid.sort_spec([("version", SortOrder::Asc)]).unwrap();
+    /// assert_eq!(grid.display().unwrap(), "version\n-------\n1.10\n1.2\n1.9\n");
+    ///
+    /// grid.set_comparator(version, |a, b| {
+    ///     let parts = |s: &str| {
+    ///         s.split('.')
+    ///             .map(|part| part.parse::<u64>().unwrap_or_default())
+    ///             .collect::<Vec<_>>()
+    ///     };
+    ///     parts(a).cmp(&parts(b))
+    /// })
+    /// .unwrap();
+    /// assert_eq!(grid.display().unwrap(), "version\n-------\n1.2\n1.9\n1.10\n");
+    /// ```
+    pub fn set_comparator(
+        &mut self,
+        header: SafeGridHeader,
+        comparator: impl Fn(&str, &str) -> Ordering + 'static,
+    ) -> Result<()> {
+        let idx = self.header_index(&header)?;
+        self.comparators.retain(|(i, _)| *i != idx);
+        self.comparators.push((idx, Rc::new(comparator)));
+        Ok(())
+    }
+
+    // sorts the lines by the sort specification, if there is one.
+    pub(crate) fn apply_sort(&mut self) {
+        if self.sort.is_empty() {
+            self.order.clear();
+            return;
+        }
+
+        let keys = self
+            .sort
+            .iter()
+            .map(|(idx, order)| {
+                let comparator = self
+                    .comparators
+                    .iter()
+                    .find(|(i, _)| i == idx)
+                    .map(|(_, c)| c.clone());
+                (self.headers.0[*idx].clone(), *order, comparator)
+            })
+            .collect::<Vec<_>>();
+
+        let headers = keys
+            .iter()
+            .map(|(h, _, _)| h.clone())
+            .collect::<HeaderList>();
+        self.materialize(&headers);
+
+        let lines = &self.lines;
+        let mut order = (0..lines.len()).collect::<Vec<_>>();
+        order.sort_by(|a, b| {
+            for (header, order, comparator) in keys.iter() {
+                let (a, b) = (contents(&lines[*a], header), contents(&lines[*b], header));
+                let ordering = match comparator {
+                    Some(comparator) => comparator(a, b),
+                    None => natural(a, b),
+                };
+
+                let ordering = match order {
+                    SortOrder::Asc => ordering,
+                    SortOrder::Desc => ordering.reverse(),
+                };
+
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+
+            Ordering::Equal
+        });
+        self.order = order;
+    }
+
+    // the positions of the lines in the order they are displayed. Lines added since they were
+    // last sorted follow the sorted lines in the order they were added.
+    pub(crate) fn line_order(&self) -> impl Iterator<Item = usize> + '_ {
+        let sorted = self.order.len().min(self.lines.len());
+        self.order[..sorted]
+            .iter()
+            .copied()
+            .chain(sorted..self.lines.len())
+    }
+}
+
+fn contents<'a>(line: &'a GridLine, header: &SafeGridHeader) -> &'a str {
+    line.items
+        .iter()
+        .find(|item| Rc::ptr_eq(&item.header, header))
+        .map(|item| item.contents.as_str())
+        .unwrap_or_default()
+}
+
+// compares items as numbers when both start with one, and as text otherwise.
+fn natural(a: &str, b: &str) -> Ordering {
+    match (leading_number(a), leading_number(b)) {
+        (Some(x), Some(y)) => x
+            .partial_cmp(&y)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.cmp(b)),
+        _ => a.cmp(b),
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{Alignment, GridHeader, GridItem, GridLine, SafeGridHeader, TTYGrid};
+use anyhow::Result;
+use rusqlite::{types::ValueRef, Rows};
+use std::{borrow::Cow, cell::RefCell, rc::Rc};
+
+impl TTYGrid {
+    /// Create a grid from the rows of a SQLite query, with a header for each column of the
+    /// statement, named after the column. NULL values are empty, and blobs are displayed as their
+    /// length. Columns holding only integers are aligned to the right, and columns holding only
+    /// numbers, some of them real, on the decimal point; see [Alignment]. Requires the `rusqlite`
+    /// feature.
+    ///
+    /// ```
+    /// use rusqlite::Connection;
+    /// use ttygrid::TTYGrid;
+    ///
+    /// let conn = Connection::open_in_memory().unwrap();
+    /// let mut stmt = conn
+    ///     .prepare("SELECT 'web-1' AS name, 3 AS cpus UNION ALL SELECT 'db-1', 16")
+    ///     .unwrap();
+    ///
+    /// let mut grid = TTYGrid::from_rows(stmt.query([]).unwrap()).unwrap();
+    /// assert_eq!(grid.column_names(), vec!["name", "cpus"]);
+    ///
+    /// let output = grid.display().unwrap();
+    /// let lines = output.lines().collect::<Vec<&str>>();
+    /// assert!(lines[2].starts_with("web-1") && lines[2].ends_with(" 3"));
+    /// assert!(lines[3].ends_with("16"));
+    /// ```
+    pub fn from_rows(mut rows: Rows<'_>) -> Result<Self> {
+        let names = rows
+            .as_ref()
+            .map(|stmt| stmt.column_names())
+            .unwrap_or_default();
+
+        let headers = names
+            .iter()
+            .map(|name| {
+                Rc::new(RefCell::new(GridHeader {
+                    text: Cow::Owned(name.to_string()),
+                    ..Default::default()
+                }))
+            })
+            .collect::<Vec<SafeGridHeader>>();
+
+        let mut grid = Self::new(headers.clone())?;
+        let mut kinds = vec![Kind::Empty; headers.len()];
+
+        while let Some(row) = rows.next()? {
+            let mut items = Vec::with_capacity(headers.len());
+
+            for (idx, header) in headers.iter().enumerate() {
+                let value = row.get_ref(idx)?;
+                kinds[idx] = kinds[idx].and(&value);
+
+                let contents = match value {
+                    ValueRef::Null => String::new(),
+                    ValueRef::Integer(i) => i.to_string(),
+                    ValueRef::Real(f) => f.to_string(),
+                    ValueRef::Text(text) => String::from_utf8_lossy(text).into_owned(),
+                    ValueRef::Blob(blob) => format!("<{} bytes>", blob.len()),
+                };
+                items.push(GridItem::new(header.clone(), contents));
+            }
+
+            grid.add_line(GridLine::from(items));
+        }
+
+        for (header, kind) in headers.iter().zip(kinds) {
+            header.borrow_mut().alignment = match kind {
+                Kind::Integer => Alignment::Right,
+                Kind::Real => Alignment::Decimal,
+                Kind::Empty | Kind::Other => Alignment::Left,
+            };
+        }
+
+        Ok(grid)
+    }
+}
+
+// the kind of the values found in a column so far, ignoring NULL.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Empty,
+    Integer,
+    Real,
+    Other,
+}
+
+impl Kind {
+    fn and(self, value: &ValueRef) -> Self {
+        match (self, value) {
+            (kind, ValueRef::Null) => kind,
+            (Kind::Empty | Kind::Integer, ValueRef::Integer(_)) => Kind::Integer,
+            (
+                Kind::Empty | Kind::Integer | Kind::Real,
+                ValueRef::Integer(_) | ValueRef::Real(_),
+            ) => Kind::Real,
+            _ => Kind::Other,
+        }
+    }
+}
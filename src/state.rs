@@ -0,0 +1,96 @@
+use crate::{FilterDisplay, ScrollPosition, SortOrder, TTYGrid};
+
+/// GridState is the part of a grid a user changes while exploring it: the sort order, the
+/// filters, which columns are hidden or shown, and where the [crate::Pager] was scrolled to.
+/// Columns are named by their keys (see [crate::GridHeader::key]), so the state can be stored
+/// between runs, such as in a configuration file. Requires the `serde` feature.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct GridState {
+    pub sort: Vec<(String, SortOrder)>,
+    pub filters: Vec<(String, String)>,
+    pub filter_display: FilterDisplay,
+    /// Columns hidden with [TTYGrid::set_hidden].
+    pub hidden: Vec<String>,
+    /// Columns shown with [TTYGrid::set_hidden], which may be hidden by default.
+    pub shown: Vec<String>,
+    pub column_offset: usize,
+    /// Where the pager was left, if it was run.
+    pub scroll: Option<ScrollPosition>,
+}
+
+impl TTYGrid {
+    /// Capture the state of the grid a user may have changed; see [GridState].
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, SortOrder};
+    ///
+    /// let name = header!("name");
+    /// let size = header!("size");
+    /// let mut grid = grid!(name.clone(), size.clone()).unwrap();
+    /// add_line!(grid, "web-1", 10);
+    /// grid.sort_spec([("size", SortOrder::Desc)]).unwrap();
+    /// grid.set_filter(name, "web").unwrap();
+    /// grid.set_hidden(size, true).unwrap();
+    ///
+    /// let state = grid.save_state();
+    /// assert_eq!(state.hidden, vec!["size"]);
+    ///
+    /// let mut other = grid!(header!("name"), header!("size")).unwrap();
+    /// other.load_state(&state);
+    /// assert_eq!(other.save_state(), state);
+    /// ```
+    pub fn save_state(&self) -> GridState {
+        let key = |idx: &usize| self.headers.0[*idx].borrow().key().to_string();
+
+        GridState {
+            sort: self
+                .sort
+                .iter()
+                .map(|(idx, order)| (key(idx), *order))
+                .collect(),
+            filters: self
+                .filters
+                .iter()
+                .map(|(idx, text)| (key(idx), text.clone()))
+                .collect(),
+            filter_display: self.filter_display,
+            hidden: self.hidden.iter().map(key).collect(),
+            shown: self.shown.iter().map(key).collect(),
+            column_offset: self.column_offset,
+            scroll: self.scroll,
+        }
+    }
+
+    /// Restore state captured with [TTYGrid::save_state], replacing the sort order, filters and
+    /// column visibility of the grid. Columns which the grid no longer has are skipped, so state
+    /// saved by an earlier version of a program can still be loaded.
+    pub fn load_state(&mut self, state: &GridState) {
+        let index =
+            |grid: &TTYGrid, key: &str| grid.headers.iter().position(|h| h.borrow().key() == key);
+
+        self.sort = state
+            .sort
+            .iter()
+            .filter_map(|(key, order)| index(self, key).map(|idx| (idx, *order)))
+            .collect();
+        self.filters = state
+            .filters
+            .iter()
+            .filter_map(|(key, text)| index(self, key).map(|idx| (idx, text.to_lowercase())))
+            .collect();
+        self.filter_display = state.filter_display;
+        self.hidden = state
+            .hidden
+            .iter()
+            .filter_map(|key| index(self, key))
+            .collect();
+        self.shown = state
+            .shown
+            .iter()
+            .filter_map(|key| index(self, key))
+            .collect();
+        self.column_offset = state.column_offset;
+        self.scroll = state.scroll;
+    }
+}
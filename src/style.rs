@@ -0,0 +1,163 @@
+use crossterm::style::{Color, Colors};
+
+/// Style collects the colors which apply to a single cell of the table, one for each source of
+/// color, and resolves them into the colors the cell is written with.
+///
+/// From the lowest to the highest precedence, the sources are:
+///
+/// 1. `theme`: the default colors of the terminal.
+/// 2. `stripe`: the primary or secondary color of the [crate::Theme], alternating by row.
+/// 3. `column`: set with [crate::TTYGrid::set_column_colors].
+/// 4. `row`: set with [crate::GridLine::set_colors].
+/// 5. `rule`: colors derived from the contents, such as vocabularies
+///    ([crate::TTYGrid::set_vocabulary]), heatmaps ([crate::TTYGrid::set_heatmap]) and change
+///    highlighting ([crate::TTYGrid::set_highlight_changes]).
+/// 6. `cell`: set with [crate::GridItem::set_colors].
+///
+/// The foreground and the background are resolved independently: a source which only sets a
+/// foreground inherits the background of the sources below it.
+///
+/// ```
+/// use crossterm::style::{Color, Colors};
+/// use ttygrid::Style;
+///
+/// let style = Style {
+///     stripe: Some(Colors::new(Color::White, Color::Black)),
+///     row: Some(Colors::new(Color::Yellow, Color::Blue)),
+///     cell: Some(Colors {
+///         foreground: Some(Color::Red),
+///         background: None,
+///     }),
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(style.resolve(), Colors::new(Color::Red, Color::Blue));
+/// assert_eq!(Style::default().resolve(), Colors::new(Color::Reset, Color::Reset));
+/// ```
+///
+/// [crate::TTYGrid::write] resolves the colors of every cell this way:
+///
+/// ```
+/// use crossterm::style::{Color, Colored, Colors};
+/// use ttygrid::{add_line, grid, header, test::FakeTerm, GridItem, GridLine};
+///
+/// // colors are written even when NO_COLOR is set
+/// Colored::set_ansi_color_disabled(false);
+///
+/// let name = header!("name");
+/// let state = header!("state");
+/// let mut term = FakeTerm::new(40, 10);
+/// let mut grid = grid!(name.clone(), state.clone()).unwrap();
+/// grid.refresh_width(&term).unwrap();
+/// grid.set_primary_color(Colors::new(Color::White, Color::Black));
+/// grid.set_secondary_color(Colors::new(Color::White, Color::Black));
+/// grid.set_column_colors(state.clone(), Colors::new(Color::Cyan, Color::Black))
+///     .unwrap();
+///
+/// // the stripe and the column
+/// add_line!(grid, "web-1", "up");
+///
+/// // the row, over the column
+/// let mut line = GridLine::from(vec![
+///     GridItem::new(name.clone(), "web-2".to_string()),
+///     GridItem::new(state.clone(), "up".to_string()),
+/// ]);
+/// line.set_colors(Colors::new(Color::Yellow, Color::Blue));
+/// grid.add_line(line);
+///
+/// // the cell's foreground, over the row's background
+/// let mut cell = GridItem::new(state, "down".to_string());
+/// cell.set_colors(Colors {
+///     foreground: Some(Color::Red),
+///     background: None,
+/// });
+/// let mut line = GridLine::from(vec![GridItem::new(name, "web-3".to_string()), cell]);
+/// line.set_colors(Colors::new(Color::Yellow, Color::Blue));
+/// grid.add_line(line);
+///
+/// grid.write(&mut term).unwrap();
+/// let states = term
+///     .spans()
+///     .iter()
+///     .skip(2)
+///     .map(|spans| {
+///         let span = spans.last().unwrap();
+///         (span.text.trim_start().to_string(), span.colors)
+///     })
+///     .collect::<Vec<_>>();
+/// assert_eq!(
+///     states,
+///     vec![
+///         ("up".to_string(), Colors::new(Color::Cyan, Color::Black)),
+///         ("web-2       up".to_string(), Colors::new(Color::Yellow, Color::Blue)),
+///         ("down".to_string(), Colors::new(Color::Red, Color::Blue)),
+///     ]
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+    pub theme: Option<Colors>,
+    pub stripe: Option<Colors>,
+    pub column: Option<Colors>,
+    pub row: Option<Colors>,
+    pub rule: Option<Colors>,
+    pub cell: Option<Colors>,
+}
+
+impl Style {
+    /// Resolve the colors of the cell. Colors which no source sets are reset.
+    pub fn resolve(&self) -> Colors {
+        let mut ret = Colors::new(Color::Reset, Color::Reset);
+
+        for colors in [
+            self.theme,
+            self.stripe,
+            self.column,
+            self.row,
+            self.rule,
+            self.cell,
+        ]
+        .iter()
+        .flatten()
+        {
+            if let Some(foreground) = colors.foreground {
+                ret.foreground = Some(foreground)
+            }
+
+            if let Some(background) = colors.background {
+                ret.background = Some(background)
+            }
+        }
+
+        ret
+    }
+}
+
+/// StyledSpan is a piece of the contents of an item with its own colors, which are layered over
+/// the colors of the cell; see [crate::GridItem::spans]. Like the other colors, they are only
+/// used by [crate::TTYGrid::write].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StyledSpan {
+    pub text: String,
+    pub colors: Colors,
+}
+
+impl StyledSpan {
+    pub fn new(text: impl Into<String>, colors: Colors) -> Self {
+        Self {
+            text: text.into(),
+            colors,
+        }
+    }
+
+    /// A span which is displayed in the colors of its cell.
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self::new(
+            text,
+            Colors {
+                foreground: None,
+                background: None,
+            },
+        )
+    }
+}
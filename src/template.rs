@@ -0,0 +1,87 @@
+use crate::{HeaderList, TTYGrid, Terminal, WidthSource};
+use anyhow::Result;
+
+/// GridTemplate captures the shape of a grid: its headers, theme and display options, without
+/// its lines. Every grid instantiated from it is independent, with headers of its own, so
+/// applications which display the same kind of table in several commands can define it once.
+///
+/// ```
+/// use ttygrid::{add_line, grid, header, GridTemplate};
+///
+/// let mut grid = grid!(header!("name"), header!("size", align = right)).unwrap();
+/// grid.set_trim_trailing_whitespace(true);
+/// let template = GridTemplate::new(&grid);
+///
+/// let mut first = template.instantiate().unwrap();
+/// add_line!(first, "a-long-name", 10);
+/// let mut second = template.instantiate().unwrap();
+/// add_line!(second, "b", 20);
+///
+/// assert!(first.display().unwrap().contains("a-long-name"));
+/// assert!(!second.display().unwrap().contains("a-long-name"));
+/// assert!(!std::rc::Rc::ptr_eq(&first.headers()[0], &second.headers()[0]));
+/// ```
+#[derive(Clone)]
+pub struct GridTemplate {
+    grid: TTYGrid,
+}
+
+impl GridTemplate {
+    /// Capture the shape of the grid. Its lines, and the state computed while it was displayed,
+    /// such as the column widths and the lines last written for change highlighting, are left
+    /// out, as are the lines dropped to fit the memory limit (see [TTYGrid::set_memory_limit]).
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, GridTemplate};
+    ///
+    /// let mut grid = grid!(header!("name")).unwrap();
+    /// grid.set_memory_limit(Some(16 * 1024));
+    /// for i in 0..10_000 {
+    ///     add_line!(grid, format!("line-{}", i));
+    /// }
+    /// assert!(grid.skipped_lines() > 0);
+    ///
+    /// let mut instance = GridTemplate::new(&grid).instantiate().unwrap();
+    /// add_line!(instance, "web-1");
+    /// add_line!(instance, "web-2");
+    /// assert_eq!(instance.skipped_lines(), 0);
+    /// assert!(!instance.display().unwrap().contains("skipped"));
+    /// assert_eq!(instance.memory_limit(), Some(16 * 1024));
+    /// ```
+    pub fn new(grid: &TTYGrid) -> Self {
+        let mut grid = grid.deep_clone();
+        grid.clear_lines();
+        grid.last_written = None;
+        grid.changes = None;
+        grid.scroll = None;
+        grid.selected = HeaderList::new();
+        grid.overflow_tables.clear();
+        grid.plain_buffer = Vec::new();
+
+        for header in grid.headers.iter() {
+            let mut header = header.borrow_mut();
+            header.max_len = None;
+        }
+
+        Self { grid }
+    }
+
+    /// Create a grid of the template, laid out for the terminal's current width.
+    pub fn instantiate(&self) -> Result<TTYGrid> {
+        self.instantiate_with(&Terminal)
+    }
+
+    /// Create a grid of the template, laid out for the width the source yields.
+    pub fn instantiate_with(&self, source: &impl WidthSource) -> Result<TTYGrid> {
+        let mut grid = self.grid.deep_clone();
+        grid.refresh_width(source)?;
+        Ok(grid)
+    }
+}
+
+impl TTYGrid {
+    /// Capture the shape of this grid as a [GridTemplate].
+    pub fn template(&self) -> GridTemplate {
+        GridTemplate::new(self)
+    }
+}
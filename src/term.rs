@@ -0,0 +1,20 @@
+use anyhow::Result;
+
+/// WidthSource yields the width, in columns, a grid is laid out for; see
+/// [crate::TTYGrid::with_width_source]. [Terminal] queries the terminal, and
+/// [crate::test::FakeTerm] yields a fixed width for tests.
+pub trait WidthSource {
+    fn width(&self) -> Result<usize>;
+}
+
+/// Terminal is the [WidthSource] of the terminal the program runs in, which [crate::TTYGrid::new]
+/// uses.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Terminal;
+
+impl WidthSource for Terminal {
+    fn width(&self) -> Result<usize> {
+        let (w, _) = crossterm::terminal::size()?;
+        Ok(w as usize)
+    }
+}
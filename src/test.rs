@@ -0,0 +1,340 @@
+//! Helpers for testing programs which use ttygrid, without a real terminal.
+use crate::{Row, StyledSpan, TTYGrid, WidthSource};
+use anyhow::{anyhow, Result};
+use crossterm::style::{Color, Colored, Colors};
+use std::io::Write;
+
+/// FakeTerm is a terminal of a fixed size which captures everything written to it. Grids can be
+/// laid out for its width with [crate::TTYGrid::with_width_source] and written to it with
+/// [crate::TTYGrid::write], and the output inspected as text or as colored spans.
+///
+/// ```
+/// use ttygrid::{add_line, header, test::FakeTerm, TTYGrid, Theme};
+///
+/// let mut term = FakeTerm::new(20, 5);
+/// let mut grid = TTYGrid::with_width_source(vec![header!("name"), header!("size")], &term)
+///     .unwrap();
+/// grid.set_theme(Theme::named("dark").unwrap());
+/// add_line!(grid, "a", 10);
+/// grid.write(&mut term).unwrap();
+///
+/// assert_eq!(
+///     term.lines(),
+///     vec!["name    size", "------------", "a       10"]
+/// );
+///
+/// // the header is written in the header colors of the theme
+/// let header = &term.spans()[0][0];
+/// assert_eq!(header.text, "name    size");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FakeTerm {
+    pub width: usize,
+    pub height: usize,
+    output: Vec<u8>,
+}
+
+impl FakeTerm {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            output: Vec::new(),
+        }
+    }
+
+    /// Everything written so far, including escape sequences.
+    pub fn output(&self) -> &[u8] {
+        &self.output
+    }
+
+    /// Discard everything written so far.
+    pub fn clear(&mut self) {
+        self.output.clear()
+    }
+
+    /// The lines written so far, without escape sequences or a trailing empty line.
+    pub fn lines(&self) -> Vec<String> {
+        self.spans()
+            .into_iter()
+            .map(|line| line.into_iter().map(|span| span.text).collect())
+            .collect()
+    }
+
+    /// The lines which would be visible: the last lines which fit in the height.
+    pub fn screen(&self) -> Vec<String> {
+        let lines = self.lines();
+        let skip = lines.len().saturating_sub(self.height);
+        lines.into_iter().skip(skip).collect()
+    }
+
+    /// The lines written so far, split into spans of text written in the same colors. Colors are
+    /// reset at the start, so text which was never colored has [Color::Reset] colors.
+    pub fn spans(&self) -> Vec<Vec<StyledSpan>> {
+        let reset = Colors::new(Color::Reset, Color::Reset);
+        let text = String::from_utf8_lossy(&self.output);
+        let mut colors = reset;
+        let mut lines = vec![Vec::new()];
+        let mut chars = text.chars().peekable();
+
+        let push = |lines: &mut Vec<Vec<StyledSpan>>, colors: Colors, c: char| {
+            let line = lines.last_mut().unwrap();
+            match line.last_mut() {
+                Some(span) if span.colors == colors => span.text.push(c),
+                _ => line.push(StyledSpan::new(c.to_string(), colors)),
+            }
+        };
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\x1b' if chars.peek() == Some(&'[') => {
+                    chars.next();
+                    let mut params = String::new();
+                    let mut command = None;
+                    for c in chars.by_ref() {
+                        if c.is_ascii_alphabetic() {
+                            command = Some(c);
+                            break;
+                        }
+                        params.push(c);
+                    }
+
+                    if command == Some('m') {
+                        match params.as_str() {
+                            "" | "0" => colors = reset,
+                            params => match Colored::parse_ansi(params) {
+                                Some(Colored::ForegroundColor(c)) => colors.foreground = Some(c),
+                                Some(Colored::BackgroundColor(c)) => colors.background = Some(c),
+                                _ => {}
+                            },
+                        }
+                    }
+                }
+                '\n' => lines.push(Vec::new()),
+                c => push(&mut lines, colors, c),
+            }
+        }
+
+        if lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+
+        lines
+    }
+}
+
+/// Lay the grid out for display and check the invariants of its layout, so programs can catch
+/// layout regressions in their own tests, such as by feeding the grid random rows:
+///
+/// - no line of the table is wider than the terminal,
+/// - every column starts at the same position on every line, after the space separating it from
+///   the column before it, and
+/// - the width of the table ([TTYGrid::total_width]) is the sum of the widths of its columns
+///   ([TTYGrid::column_width]).
+///
+/// An error describing the first violation is returned, as are errors displaying the grid, such
+/// as when the terminal is too small for it. Lines drawn by a row renderer (see
+/// [TTYGrid::set_row_renderer]) are only checked for their width. Raw items (see
+/// [crate::GridItem::raw]), such as the icons of [crate::IconColumn]s, are measured as the width
+/// they declare.
+///
+/// ```
+/// use ttygrid::{add_line, grid, header, test::check_layout};
+///
+/// let mut grid = grid!(header!("name"), header!("size")).unwrap();
+/// for _ in 0..20 {
+///     let name = "x".repeat(rand::random::<u32>() as usize % 40 + 1);
+///     add_line!(grid, name, rand::random::<u32>());
+/// }
+///
+/// check_layout(&mut grid).unwrap();
+/// ```
+pub fn check_layout(grid: &mut TTYGrid) -> Result<()> {
+    grid.display()?;
+
+    let widths = grid.selected.iter().map(|h| grid.column_width(h.clone()));
+    let sum = widths
+        .sum::<Option<usize>>()
+        .ok_or_else(|| anyhow!("a selected column has no width"))?;
+    if sum != grid.total_width() {
+        return Err(anyhow!(
+            "table is {} cols wide, but its columns add up to {}",
+            grid.total_width(),
+            sum
+        ));
+    }
+
+    let margin = grid.table_margin();
+    let starts = grid
+        .selected_widths()
+        .iter()
+        .scan(margin, |start, width| {
+            *start += width;
+            Some(*start)
+        })
+        .collect::<Vec<usize>>();
+
+    // the contents and declared widths of the raw items of each line, which are measured as
+    // their declared width rather than their characters.
+    let raw = grid
+        .display_lines()
+        .into_iter()
+        .map(|(idx, line)| {
+            let items = line
+                .items
+                .iter()
+                .filter_map(|item| item.raw.map(|width| (item.contents.clone(), width)))
+                .collect::<Vec<(String, usize)>>();
+            (idx, items)
+        })
+        .filter(|(_, items)| !items.is_empty())
+        .collect::<Vec<(usize, Vec<(String, usize)>)>>();
+
+    for (n, (row, segments)) in grid.styled_rows()?.into_iter().enumerate() {
+        let mut pending = match row {
+            Row::Line(idx) => raw
+                .iter()
+                .find(|(i, _)| *i == idx)
+                .map(|(_, items)| items.iter().collect::<Vec<_>>())
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        }
+        .into_iter()
+        .peekable();
+
+        let line = segments
+            .into_iter()
+            .map(|(_, text)| match pending.peek() {
+                Some((contents, width)) if text.starts_with(contents.as_str()) => {
+                    let rest = &text[contents.len()..];
+                    let text = format!("{}{}", "#".repeat(*width), rest);
+                    pending.next();
+                    text
+                }
+                _ => text,
+            })
+            .collect::<String>()
+            .chars()
+            .collect::<Vec<char>>();
+
+        if line.len() > grid.width {
+            return Err(anyhow!(
+                "line {} is {} cols wide, wider than the terminal's {}",
+                n,
+                line.len(),
+                grid.width
+            ));
+        }
+
+        let aligned = match row {
+            Row::Header => true,
+            Row::Line(_) => grid.row_renderer.is_none(),
+            Row::Delimiter | Row::Other => false,
+        };
+        if !aligned {
+            continue;
+        }
+
+        // the last start is the end of the table, which is not a column
+        for (column, start) in starts
+            .iter()
+            .take(starts.len().saturating_sub(1))
+            .enumerate()
+        {
+            if line.get(start - 1).is_some_and(|c| *c != ' ') {
+                return Err(anyhow!(
+                    "column {} of line {} runs into the next, which starts at col {}: {:?}",
+                    column + 1,
+                    n,
+                    start - margin,
+                    line.iter().collect::<String>()
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Lay the grid out for a terminal of the width and compare its display (see
+/// [TTYGrid::display]) to the expected text, yielding an error which lists the lines side by
+/// side when they differ; [crate::assert_grid_eq!] panics with it. So expectations can be written
+/// as indented string literals, empty lines at the start and end of the expected text are
+/// ignored, as is the indentation its lines share. Trailing whitespace is ignored on both sides.
+pub fn compare_grid(grid: &mut TTYGrid, expected: &str, width: usize) -> Result<()> {
+    let terminal_width = std::mem::replace(&mut grid.width, width);
+    let output = grid.display();
+    grid.width = terminal_width;
+
+    let actual = output?
+        .lines()
+        .map(|l| l.trim_end().to_string())
+        .collect::<Vec<String>>();
+    let expected = dedent(expected);
+    if actual == expected {
+        return Ok(());
+    }
+
+    let mut message = format!(
+        "grid does not match the expectation at width {} (-expected +actual):\n",
+        width
+    );
+    for n in 0..actual.len().max(expected.len()) {
+        match (expected.get(n), actual.get(n)) {
+            (Some(e), Some(a)) if e == a => message += &format!("  {}\n", e),
+            (e, a) => {
+                if let Some(e) = e {
+                    message += &format!("- {}\n", e);
+                }
+                if let Some(a) = a {
+                    message += &format!("+ {}\n", a);
+                }
+            }
+        }
+    }
+
+    Err(anyhow!(message))
+}
+
+// the lines of the text without the empty lines around them and their common indentation.
+fn dedent(text: &str) -> Vec<String> {
+    let lines = text.lines().map(str::trim_end).collect::<Vec<&str>>();
+    let start = lines
+        .iter()
+        .position(|l| !l.is_empty())
+        .unwrap_or(lines.len());
+    let end = lines
+        .iter()
+        .rposition(|l| !l.is_empty())
+        .map_or(start, |e| e + 1);
+    let lines = &lines[start..end];
+
+    let indent = lines
+        .iter()
+        .filter(|l| !l.is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or_default();
+
+    lines
+        .iter()
+        .map(|l| l.get(indent..).unwrap_or_default().to_string())
+        .collect()
+}
+
+impl WidthSource for FakeTerm {
+    fn width(&self) -> Result<usize> {
+        Ok(self.width)
+    }
+}
+
+impl Write for FakeTerm {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.output.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,296 @@
+use anyhow::{anyhow, Result};
+use crossterm::style::{Color, Colors};
+use std::convert::TryFrom;
+
+/// Theme holds the colors used by [crate::TTYGrid::write] when rendering a table. The individual
+/// colors can also be set through the setters on [crate::TTYGrid].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Theme {
+    /// The color of the header line.
+    #[cfg_attr(feature = "serde", serde(with = "colors"))]
+    pub header: Colors,
+    /// The color of the dashes between the header and the content.
+    #[cfg_attr(feature = "serde", serde(with = "colors"))]
+    pub delimiter: Colors,
+    /// The color of even rows.
+    #[cfg_attr(feature = "serde", serde(with = "colors"))]
+    pub primary: Colors,
+    /// The color of odd rows.
+    #[cfg_attr(feature = "serde", serde(with = "colors"))]
+    pub secondary: Colors,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            header: Colors::new(Color::Reset, Color::Reset),
+            delimiter: Colors::new(Color::Reset, Color::Reset),
+            primary: Colors::new(Color::Reset, Color::Reset),
+            secondary: Colors::new(Color::Reset, Color::Reset),
+        }
+    }
+}
+
+impl Theme {
+    /// Yield one of the themes which ship with ttygrid: "default", which leaves the terminal's
+    /// colors alone, "dark" or "light".
+    pub fn named(name: &str) -> Option<Self> {
+        let theme = match name {
+            "default" => Self::default(),
+            "dark" => Self {
+                header: Colors::new(Color::Cyan, Color::Reset),
+                delimiter: Colors::new(Color::DarkGrey, Color::Reset),
+                primary: Colors::new(Color::Reset, Color::Reset),
+                secondary: Colors::new(Color::Grey, Color::Reset),
+            },
+            "light" => Self {
+                header: Colors::new(Color::DarkBlue, Color::Reset),
+                delimiter: Colors::new(Color::Grey, Color::Reset),
+                primary: Colors::new(Color::Reset, Color::Reset),
+                secondary: Colors::new(Color::DarkGrey, Color::Reset),
+            },
+            _ => return None,
+        };
+
+        Some(theme)
+    }
+
+    /// Build a theme from the environment, so the users of a program can customize its tables:
+    ///
+    /// - `TTYGRID_THEME` names the theme to start from; see [Theme::named].
+    /// - `TTYGRID_COLORS` then overrides individual colors, in the style of `LS_COLORS`: a
+    ///   colon-separated list of `key=codes`, where the key is one of `hd` (header), `dl`
+    ///   (delimiter), `pr` (primary) or `se` (secondary), and the codes are ANSI SGR color codes,
+    ///   such as `34`, `1;97;44` or `38;5;208`. Codes which are not colors, or are out of range,
+    ///   are ignored.
+    ///
+    /// If `NO_COLOR` is set, the default theme is used regardless. An error is returned when the
+    /// variables can not be understood.
+    ///
+    /// ```
+    /// use crossterm::style::{Color, Colors};
+    /// use ttygrid::Theme;
+    ///
+    /// std::env::remove_var("NO_COLOR");
+    /// std::env::set_var("TTYGRID_THEME", "dark");
+    /// std::env::set_var("TTYGRID_COLORS", "hd=1;33:se=38;5;244;48;5;236:pr=38;5;300;32");
+    ///
+    /// let theme = Theme::from_env().unwrap();
+    /// assert_eq!(theme.header, Colors::new(Color::DarkYellow, Color::Reset));
+    /// assert_eq!(theme.primary, Colors::new(Color::DarkGreen, Color::Reset));
+    /// assert_eq!(
+    ///     theme.secondary,
+    ///     Colors::new(Color::AnsiValue(244), Color::AnsiValue(236))
+    /// );
+    /// assert_eq!(theme.delimiter, Theme::named("dark").unwrap().delimiter);
+    /// ```
+    pub fn from_env() -> Result<Self> {
+        if std::env::var_os("NO_COLOR").is_some() {
+            return Ok(Self::default());
+        }
+
+        let mut theme = match std::env::var("TTYGRID_THEME") {
+            Ok(name) => Self::named(&name).ok_or_else(|| anyhow!("unknown theme {:?}", name))?,
+            Err(_) => Self::default(),
+        };
+
+        if let Ok(spec) = std::env::var("TTYGRID_COLORS") {
+            theme.apply(&spec)?;
+        }
+
+        Ok(theme)
+    }
+
+    // applies a TTYGRID_COLORS specification to the theme.
+    fn apply(&mut self, spec: &str) -> Result<()> {
+        for entry in spec.split(':').filter(|e| !e.is_empty()) {
+            let (key, codes) = entry
+                .split_once('=')
+                .ok_or_else(|| anyhow!("invalid color entry {:?}", entry))?;
+
+            let colors = match key {
+                "hd" => &mut self.header,
+                "dl" => &mut self.delimiter,
+                "pr" => &mut self.primary,
+                "se" => &mut self.secondary,
+                _ => return Err(anyhow!("unknown color key {:?}", key)),
+            };
+
+            *colors = parse_sgr(codes);
+        }
+
+        Ok(())
+    }
+}
+
+// parses ANSI SGR codes into the colors they select. Unset colors are reset, and codes which
+// are not known or out of range, such as 256 color indices above 255, are skipped.
+fn parse_sgr(codes: &str) -> Colors {
+    // an empty code is a reset, as in "\x1b[m"
+    let codes = codes
+        .split(';')
+        .map(|c| {
+            if c.is_empty() {
+                Some(0)
+            } else {
+                c.parse::<u32>().ok()
+            }
+        })
+        .collect::<Vec<Option<u32>>>();
+
+    let mut colors = Colors::new(Color::Reset, Color::Reset);
+    let mut iter = codes.into_iter();
+    let component = |iter: &mut std::vec::IntoIter<Option<u32>>| {
+        iter.next().flatten().and_then(|c| u8::try_from(c).ok())
+    };
+
+    while let Some(code) = iter.next() {
+        let code = match code {
+            Some(code) => code,
+            None => continue,
+        };
+
+        match code {
+            0 => colors = Colors::new(Color::Reset, Color::Reset),
+            30..=37 => colors.foreground = Some(basic((code - 30) as u8)),
+            90..=97 => colors.foreground = Some(basic((code - 90 + 8) as u8)),
+            40..=47 => colors.background = Some(basic((code - 40) as u8)),
+            100..=107 => colors.background = Some(basic((code - 100 + 8) as u8)),
+            39 => colors.foreground = Some(Color::Reset),
+            49 => colors.background = Some(Color::Reset),
+            38 | 48 => {
+                // the components are consumed even when they are out of range
+                let color = match iter.next().flatten() {
+                    Some(5) => component(&mut iter).map(Color::AnsiValue),
+                    Some(2) => {
+                        let (r, g, b) = (
+                            component(&mut iter),
+                            component(&mut iter),
+                            component(&mut iter),
+                        );
+                        match (r, g, b) {
+                            (Some(r), Some(g), Some(b)) => Some(Color::Rgb { r, g, b }),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                };
+
+                match color {
+                    Some(color) if code == 38 => colors.foreground = Some(color),
+                    Some(color) => colors.background = Some(color),
+                    None => {}
+                }
+            }
+            _ => {} // attributes such as bold
+        }
+    }
+
+    colors
+}
+
+// yields the named color for one of the 16 basic terminal colors.
+pub(crate) fn basic(code: u8) -> Color {
+    [
+        Color::Black,
+        Color::DarkRed,
+        Color::DarkGreen,
+        Color::DarkYellow,
+        Color::DarkBlue,
+        Color::DarkMagenta,
+        Color::DarkCyan,
+        Color::Grey,
+        Color::DarkGrey,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::White,
+    ][code as usize]
+}
+
+// crossterm can (de)serialize a Color, but not Colors, and refuses to serialize Color::Reset. A
+// missing color is treated as a reset.
+#[cfg(feature = "serde")]
+mod colors {
+    use crossterm::style::{Color, Colors};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct ColorsDef {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        foreground: Option<Color>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        background: Option<Color>,
+    }
+
+    fn to_def(color: Option<Color>) -> Option<Color> {
+        color.filter(|c| *c != Color::Reset)
+    }
+
+    pub fn serialize<S: Serializer>(colors: &Colors, serializer: S) -> Result<S::Ok, S::Error> {
+        ColorsDef {
+            foreground: to_def(colors.foreground),
+            background: to_def(colors.background),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Colors, D::Error> {
+        let def = ColorsDef::deserialize(deserializer)?;
+        Ok(Colors::new(
+            def.foreground.unwrap_or(Color::Reset),
+            def.background.unwrap_or(Color::Reset),
+        ))
+    }
+}
+
+/// ColorScale maps a position between 0.0 and 1.0 to a color. It is used by
+/// [crate::TTYGrid::set_heatmap] to color numeric cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ColorScale {
+    /// Interpolate between two RGB colors. This requires a terminal with true color support.
+    Rgb((u8, u8, u8), (u8, u8, u8)),
+    /// Split the range evenly between the colors, lowest first.
+    Steps(Vec<Color>),
+}
+
+impl ColorScale {
+    /// A green, yellow, red scale which works on any color terminal.
+    pub fn traffic_light() -> Self {
+        ColorScale::Steps(vec![Color::Green, Color::Yellow, Color::Red])
+    }
+
+    /// Yield the color for the position, which is clamped to 0.0 through 1.0.
+    pub fn color_for(&self, position: f64) -> Color {
+        let position = if position.is_nan() {
+            0.0
+        } else {
+            position.clamp(0.0, 1.0)
+        };
+
+        match self {
+            ColorScale::Rgb(from, to) => {
+                let mix =
+                    |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * position).round() as u8;
+                Color::Rgb {
+                    r: mix(from.0, to.0),
+                    g: mix(from.1, to.1),
+                    b: mix(from.2, to.2),
+                }
+            }
+            ColorScale::Steps(colors) => {
+                if colors.is_empty() {
+                    return Color::Reset;
+                }
+
+                let idx = (position * colors.len() as f64) as usize;
+                colors[idx.min(colors.len() - 1)]
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{Priority, TTYGrid};
+use std::rc::Rc;
+
+/// UninformativeColumns controls what happens to columns which carry no information: those whose
+/// items are all empty, or, with more than one line, all the same. See
+/// [TTYGrid::set_uninformative_columns]. Only the lines which are displayed are considered, and
+/// [Priority::Essential] columns are always kept.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum UninformativeColumns {
+    /// Lay the columns out like any other.
+    #[default]
+    Keep,
+    /// Drop the columns before any other when the terminal is too narrow, even those with the
+    /// same priority. They are reported as [Priority::Debug] by [TTYGrid::explain_layout].
+    Deprioritize,
+    /// Leave the columns out unless they are explicitly shown with [TTYGrid::set_hidden].
+    Hide,
+}
+
+impl TTYGrid {
+    /// Select what happens to columns which carry no information, so the width of the terminal
+    /// goes to the columns which do. The default is [UninformativeColumns::Keep].
+    ///
+    /// ```
+    /// use ttygrid::{add_line, grid, header, test::FakeTerm, UninformativeColumns};
+    ///
+    /// let mut grid = grid!(header!("name"), header!("region"), header!("notes")).unwrap();
+    /// add_line!(grid, "web-1", "us-east", "");
+    /// add_line!(grid, "web-2", "us-east", "");
+    ///
+    /// grid.set_uninformative_columns(UninformativeColumns::Hide);
+    /// let output = grid.display().unwrap();
+    /// assert_eq!(output.lines().next(), Some("name"));
+    ///
+    /// let mut grid = grid!(header!("region"), header!("name"), header!("owner")).unwrap();
+    /// add_line!(grid, "us-east", "web-1", "alice");
+    /// add_line!(grid, "us-east", "web-2", "bob");
+    ///
+    /// grid.set_uninformative_columns(UninformativeColumns::Deprioritize);
+    /// grid.refresh_width(&FakeTerm::new(30, 10)).unwrap();
+    /// let output = grid.display().unwrap();
+    /// assert_eq!(output.lines().next(), Some("name        owner"));
+    ///
+    /// grid.refresh_width(&FakeTerm::new(80, 10)).unwrap();
+    /// let output = grid.display().unwrap();
+    /// assert!(output.starts_with("region"));
+    /// ```
+    pub fn set_uninformative_columns(&mut self, uninformative: UninformativeColumns) {
+        self.uninformative = uninformative
+    }
+
+    pub fn uninformative_columns(&self) -> UninformativeColumns {
+        self.uninformative
+    }
+
+    // whether the column at the position is hidden for carrying no information.
+    pub(crate) fn hidden_uninformative(&self, idx: usize) -> bool {
+        self.uninformative == UninformativeColumns::Hide
+            && !self.shown.contains(&idx)
+            && self.uninformative_index(idx)
+    }
+
+    // the priority the column at the position is laid out with. Marker and icon columns are
+    // laid out like essential ones, so every other column is dropped before them.
+    pub(crate) fn layout_priority(&self, idx: usize) -> usize {
+        if self.marker_column(idx).is_some() || self.icon_column(idx).is_some() {
+            Priority::Essential.into()
+        } else if self.deprioritized(idx) {
+            Priority::Debug.into()
+        } else {
+            self.headers.0[idx].borrow().priority
+        }
+    }
+
+    // whether the column at the position is dropped before every ordinary column for carrying
+    // no information. Marker and icon columns never are.
+    pub(crate) fn deprioritized(&self, idx: usize) -> bool {
+        self.uninformative == UninformativeColumns::Deprioritize
+            && self.marker_column(idx).is_none()
+            && self.icon_column(idx).is_none()
+            && self.uninformative_index(idx)
+    }
+
+    // whether the items of the column at the position are all empty or all the same. Columns
+    // with lazy items which have not been produced yet are assumed to be informative.
+    fn uninformative_index(&self, idx: usize) -> bool {
+        let header = &self.headers.0[idx];
+        if header.borrow().is_essential() {
+            return false;
+        }
+
+        let mut first: Option<&str> = None;
+        let mut count = 0;
+        for line in (0..self.lines.len()).filter(|i| self.line_shown(*i)) {
+            let item = self.lines[line]
+                .items
+                .iter()
+                .find(|item| Rc::ptr_eq(&item.header, header));
+
+            let contents = match item {
+                Some(item) if item.lazy.is_some() || item.sparkline.is_some() => return false,
+                Some(item) => item.contents.as_str(),
+                None => "",
+            };
+
+            match first {
+                Some(first) if first != contents => return false,
+                _ => first = Some(contents),
+            }
+            count += 1;
+        }
+
+        first.is_some_and(|first| first.is_empty() || count > 1)
+    }
+}
@@ -0,0 +1,230 @@
+use crate::{HeaderId, TTYGrid, Width};
+use std::fmt;
+
+/// ValidationError describes a problem with the configuration of a grid which would make it fail
+/// to display, or display differently than intended; see [TTYGrid::validate].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The grid has no headers.
+    NoHeaders,
+    /// The header has the same key as an earlier one, so it cannot be told apart by name, such as
+    /// by [TTYGrid::sort_spec] or in JSON exports.
+    DuplicateHeader { header: HeaderId, first: HeaderId },
+    /// The minimum size of the header is larger than its maximum or fixed width.
+    MinSizeExceedsWidth {
+        header: HeaderId,
+        min_size: usize,
+        width: usize,
+    },
+    /// The displayed headers with fixed widths need more columns than the terminal has, so they
+    /// can never be displayed together.
+    FixedWidthsExceedTerminal {
+        headers: Vec<HeaderId>,
+        needed: usize,
+        width: usize,
+    },
+    /// The contents of the item of the line, at its position in the grid, failed the validator of
+    /// the header; see [crate::GridHeader::set_validator].
+    InvalidContent {
+        line: usize,
+        header: HeaderId,
+        message: String,
+    },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidationError::NoHeaders => write!(formatter, "the grid has no headers"),
+            ValidationError::DuplicateHeader { header, first } => write!(
+                formatter,
+                "header '{}' (column {}) has the same key as column {}: '{}'",
+                header.text(),
+                header.index() + 1,
+                first.index() + 1,
+                header.key()
+            ),
+            ValidationError::MinSizeExceedsWidth {
+                header,
+                min_size,
+                width,
+            } => write!(
+                formatter,
+                "header '{}' has a minimum size of {}, but it is at most {} wide",
+                header.text(),
+                min_size,
+                width
+            ),
+            ValidationError::FixedWidthsExceedTerminal {
+                headers,
+                needed,
+                width,
+            } => write!(
+                formatter,
+                "the fixed widths of {} need {} columns, but only {} are available",
+                headers
+                    .iter()
+                    .map(|h| h.text())
+                    .collect::<Vec<&str>>()
+                    .join(", "),
+                needed,
+                width
+            ),
+            ValidationError::InvalidContent {
+                line,
+                header,
+                message,
+            } => write!(
+                formatter,
+                "line {}, column '{}': {}",
+                line + 1,
+                header.text(),
+                message
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl TTYGrid {
+    /// Check the configuration of the grid, yielding every problem found, so they can be reported
+    /// before the grid is displayed instead of surfacing as a layout error. An empty list means
+    /// the grid is valid. This includes the contents of lines which failed the validators of
+    /// their headers; see [TTYGrid::content_errors].
+    ///
+    /// ```
+    /// use std::{cell::RefCell, rc::Rc};
+    /// use ttygrid::{grid, header, GridHeader, ValidationError};
+    ///
+    /// let narrow = Rc::new(RefCell::new(
+    ///     GridHeader::default()
+    ///         .set_text("path")
+    ///         .set_min_size(20)
+    ///         .set_max_width(10),
+    /// ));
+    /// let grid = grid!(header!("name"), header!("name"), narrow).unwrap();
+    ///
+    /// let errors = grid.validate();
+    /// assert_eq!(errors.len(), 2);
+    /// assert!(matches!(errors[0], ValidationError::DuplicateHeader { .. }));
+    /// assert!(matches!(
+    ///     errors[1],
+    ///     ValidationError::MinSizeExceedsWidth { min_size: 20, width: 10, .. }
+    /// ));
+    /// ```
+    ///
+    /// Fixed widths are checked against the width of the terminal:
+    ///
+    /// ```
+    /// use ttygrid::{grid, header, test::FakeTerm, TTYGrid, ValidationError, Width};
+    ///
+    /// let mut grid = grid!(
+    ///     header!("name", width = Width::Chars(20)),
+    ///     header!("path", width = Width::Chars(30)),
+    ///     header!("size")
+    /// )
+    /// .unwrap();
+    /// grid.refresh_width(&FakeTerm::new(40, 10)).unwrap();
+    ///
+    /// let errors = grid.validate();
+    /// assert_eq!(errors.len(), 1);
+    /// assert!(matches!(
+    ///     errors[0],
+    ///     ValidationError::FixedWidthsExceedTerminal { needed: 64, width: 40, .. }
+    /// ));
+    /// assert_eq!(
+    ///     errors[0].to_string(),
+    ///     "the fixed widths of name, path need 64 columns, but only 40 are available"
+    /// );
+    ///
+    /// let empty = TTYGrid::new(Vec::new()).unwrap();
+    /// assert_eq!(empty.validate(), vec![ValidationError::NoHeaders]);
+    /// ```
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut ret = Vec::new();
+
+        if self.headers.is_empty() {
+            ret.push(ValidationError::NoHeaders);
+        }
+
+        for (idx, header) in self.headers.iter().enumerate() {
+            let header = header.borrow();
+
+            if let Some(first) = self
+                .headers
+                .iter()
+                .position(|h| h.borrow().key() == header.key())
+            {
+                if first < idx {
+                    ret.push(ValidationError::DuplicateHeader {
+                        header: self.header_id(idx),
+                        first: self.header_id(first),
+                    });
+                }
+            }
+
+            let width = match (header.width, header.max_width) {
+                (Some(Width::Chars(chars)), _) => Some(chars),
+                (_, Some(max_width)) => Some(max_width),
+                _ => None,
+            };
+
+            if let (Some(min_size), Some(width)) = (header.min_size, width) {
+                if min_size > width {
+                    ret.push(ValidationError::MinSizeExceedsWidth {
+                        header: self.header_id(idx),
+                        min_size,
+                        width,
+                    });
+                }
+            }
+        }
+
+        let mut headers = Vec::new();
+        let mut needed = 0;
+        for idx in (0..self.headers.len()).filter(|idx| !self.hidden_index(*idx)) {
+            let header = self.headers.0[idx].borrow();
+            let width = match header.width {
+                Some(Width::Chars(chars)) => chars + header.gap(),
+                Some(Width::Percent(percent)) => self.width * percent / 100,
+                _ => continue,
+            };
+
+            headers.push(self.header_id(idx));
+            needed += width;
+        }
+
+        if needed > self.width {
+            ret.push(ValidationError::FixedWidthsExceedTerminal {
+                headers,
+                needed,
+                width: self.width,
+            });
+        }
+
+        ret.extend(self.content_errors());
+        ret
+    }
+
+    /// Yield the items which failed the validators of their headers when their lines were added,
+    /// in the order of the lines; see [crate::GridHeader::set_validator].
+    pub fn content_errors(&self) -> Vec<ValidationError> {
+        let mut ret = Vec::new();
+
+        for (idx, line) in self.lines.iter().enumerate() {
+            for (pos, message) in line.errors.iter() {
+                let header = &line.items[*pos].header;
+                if let Ok(index) = self.header_index(header) {
+                    ret.push(ValidationError::InvalidContent {
+                        line: idx,
+                        header: self.header_id(index),
+                        message: message.clone(),
+                    });
+                }
+            }
+        }
+
+        ret
+    }
+}
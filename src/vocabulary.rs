@@ -0,0 +1,178 @@
+use crate::{SafeGridHeader, TTYGrid};
+use anyhow::Result;
+use crossterm::style::{Color, Colors};
+
+/// Vocabulary colors the common values of status columns, such as `ok`, `warn` and `error`, so
+/// tables of health checks or services are readable at a glance; see [TTYGrid::set_vocabulary].
+/// Values are recognized regardless of case and surrounding whitespace, and only the foreground
+/// color is changed. Vocabularies can be built from the recognizers below, and their colors
+/// changed or extended with [Vocabulary::set_colors].
+///
+/// The default vocabulary recognizes all of them:
+///
+/// - severities: `ok`, `success`, `pass`, `healthy` are green; `warn`, `warning`, `degraded`
+///   are yellow; `error`, `fail`, `failed`, `critical`, `fatal` are red.
+/// - booleans: `true`, `yes`, `on`, `enabled` are green; `false`, `no`, `off`, `disabled` are
+///   red.
+/// - `up` is green, and `down` red.
+/// - process states: `running`, `started`, `active` are green; `starting`, `stopping`,
+///   `pending`, `restarting` are yellow; `stopped`, `exited`, `inactive`, `dead` are red.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Vocabulary {
+    colors: Vec<(String, Colors)>,
+}
+
+impl Default for Vocabulary {
+    fn default() -> Self {
+        Self::severity()
+            .merge(Self::boolean())
+            .merge(Self::up_down())
+            .merge(Self::running())
+    }
+}
+
+impl Vocabulary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A vocabulary which recognizes nothing, to be filled with [Vocabulary::set_colors].
+    pub fn empty() -> Self {
+        Self { colors: Vec::new() }
+    }
+
+    /// Severities: `ok`, `warn`, `error` and their synonyms.
+    pub fn severity() -> Self {
+        Self::from_words(&[
+            (
+                &["ok", "okay", "success", "pass", "passed", "healthy"],
+                Color::Green,
+            ),
+            (&["warn", "warning", "degraded"], Color::Yellow),
+            (
+                &["error", "err", "fail", "failed", "critical", "fatal"],
+                Color::Red,
+            ),
+        ])
+    }
+
+    /// Booleans: `true`, `false` and their synonyms.
+    pub fn boolean() -> Self {
+        Self::from_words(&[
+            (&["true", "yes", "on", "enabled"], Color::Green),
+            (&["false", "no", "off", "disabled"], Color::Red),
+        ])
+    }
+
+    /// `up` and `down`.
+    pub fn up_down() -> Self {
+        Self::from_words(&[(&["up"], Color::Green), (&["down"], Color::Red)])
+    }
+
+    /// Process states: `running`, `stopped` and the states between them.
+    pub fn running() -> Self {
+        Self::from_words(&[
+            (&["running", "started", "active"], Color::Green),
+            (
+                &["starting", "stopping", "pending", "restarting"],
+                Color::Yellow,
+            ),
+            (&["stopped", "exited", "inactive", "dead"], Color::Red),
+        ])
+    }
+
+    /// Recognize the values of the other vocabulary as well. Its colors take precedence for the
+    /// values both recognize.
+    pub fn merge(mut self, other: Vocabulary) -> Self {
+        for (value, colors) in other.colors {
+            self = self.set_colors(&value, colors);
+        }
+        self
+    }
+
+    /// Set the colors of the value, replacing any it had.
+    pub fn set_colors(mut self, value: &str, colors: Colors) -> Self {
+        let value = value.trim().to_lowercase();
+        self.colors.retain(|(v, _)| *v != value);
+        self.colors.push((value, colors));
+        self
+    }
+
+    /// The colors of the value, if it is recognized.
+    pub fn colors(&self, value: &str) -> Option<Colors> {
+        let value = value.trim().to_lowercase();
+        self.colors
+            .iter()
+            .find(|(v, _)| *v == value)
+            .map(|(_, colors)| *colors)
+    }
+
+    fn from_words(words: &[(&[&str], Color)]) -> Self {
+        let mut colors = Vec::new();
+        for (values, color) in words {
+            for value in values.iter() {
+                let foreground = Colors {
+                    foreground: Some(*color),
+                    background: None,
+                };
+                colors.push((value.to_string(), foreground));
+            }
+        }
+
+        Self { colors }
+    }
+}
+
+impl TTYGrid {
+    /// Color the values of the header's column the vocabulary recognizes; see [Vocabulary].
+    /// These colors are the lowest of the colors derived from the contents (see [crate::Style]),
+    /// so heatmaps, marker columns and change highlighting override them, as do the colors of
+    /// rows and cells. Like the other colors, they are only used by [TTYGrid::write].
+    ///
+    /// ```
+    /// use crossterm::style::{Color, Colored, Colors};
+    /// use ttygrid::{add_line, grid, header, test::FakeTerm, Vocabulary};
+    ///
+    /// // colors are written even when NO_COLOR is set
+    /// Colored::set_ansi_color_disabled(false);
+    ///
+    /// let status = header!("status");
+    /// let mut term = FakeTerm::new(20, 5);
+    /// let mut grid = grid!(header!("name"), status.clone()).unwrap();
+    /// grid.refresh_width(&term).unwrap();
+    /// let vocabulary = Vocabulary::severity().set_colors(
+    ///     "warn",
+    ///     Colors::new(Color::Black, Color::Yellow),
+    /// );
+    /// grid.set_vocabulary(status, vocabulary).unwrap();
+    /// add_line!(grid, "db", "OK");
+    /// add_line!(grid, "web", "warn");
+    ///
+    /// grid.write(&mut term).unwrap();
+    /// let ok = term.spans()[2].iter().find(|s| s.text.starts_with("OK")).unwrap().colors;
+    /// assert_eq!(ok.foreground, Some(Color::Green));
+    /// let warn = term.spans()[3].iter().find(|s| s.text.starts_with("warn")).unwrap().colors;
+    /// assert_eq!(warn.background, Some(Color::Yellow));
+    /// ```
+    pub fn set_vocabulary(&mut self, header: SafeGridHeader, vocabulary: Vocabulary) -> Result<()> {
+        let idx = self.header_index(&header)?;
+        self.vocabularies.retain(|(i, _)| *i != idx);
+        self.vocabularies.push((idx, vocabulary));
+        Ok(())
+    }
+
+    /// Stop coloring the values of the header's column by a vocabulary.
+    pub fn clear_vocabulary(&mut self, header: SafeGridHeader) -> Result<()> {
+        let idx = self.header_index(&header)?;
+        self.vocabularies.retain(|(i, _)| *i != idx);
+        Ok(())
+    }
+
+    // the colors the vocabulary of the column at the position gives the contents, if any.
+    pub(crate) fn vocabulary_colors(&self, idx: usize, contents: &str) -> Option<Colors> {
+        self.vocabularies
+            .iter()
+            .find(|(i, _)| *i == idx)
+            .and_then(|(_, vocabulary)| vocabulary.colors(contents))
+    }
+}
@@ -0,0 +1,120 @@
+// tests of GridConfig serialization: a config written to JSON and read back describes the same
+// grid.
+#![cfg(feature = "serde")]
+
+use ttygrid::{
+    add_line, grid, header, GridConfig, OverflowPolicy, Pin, SortOrder, TTYGrid, TableAlign, Theme,
+    Width,
+};
+
+fn config() -> GridConfig {
+    let mut grid = grid!(
+        header!("pid", align = right),
+        header!("command", max = 20),
+        header!("state", width = Width::Weight(2))
+    )
+    .unwrap();
+    grid.set_theme(Theme::named("dark").unwrap());
+    grid.config()
+}
+
+#[test]
+fn config_round_trip() {
+    let config = config();
+    let json = serde_json::to_string(&config).unwrap();
+    let loaded: GridConfig = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded, config);
+}
+
+#[test]
+fn grid_from_loaded_config() {
+    let json = serde_json::to_string(&config()).unwrap();
+    let mut grid = TTYGrid::from_config(serde_json::from_str(&json).unwrap()).unwrap();
+    assert_eq!(grid.config(), config());
+
+    add_line!(grid, "1", "init", "sleeping");
+    let output = grid.display().unwrap();
+    assert!(output.starts_with("pid"));
+}
+
+#[test]
+fn partial_config() {
+    let loaded: GridConfig = serde_json::from_str(r#"{"headers": [{"text": "pid"}]}"#).unwrap();
+    assert_eq!(loaded.theme, Theme::default());
+    assert_eq!(loaded.headers.len(), 1);
+    assert_eq!(loaded.headers[0].text(), "pid");
+}
+
+// a grid with every layout option of the config changed from its default.
+fn layout_config() -> GridConfig {
+    let (pid, command, state) = (header!("pid"), header!("command"), header!("state"));
+    let mut grid = grid!(
+        pid.clone(),
+        command.clone(),
+        state.clone(),
+        header!("debug", hidden = true),
+        header!("node", pin = Pin::Right)
+    )
+    .unwrap();
+    let debug = grid.headers()[3].clone();
+    grid.set_hidden(state, true).unwrap();
+    grid.set_hidden(debug, false).unwrap();
+    grid.set_frozen(pid.clone(), true).unwrap();
+    grid.define_view("compact", [command.clone(), pid]).unwrap();
+    grid.set_active_view("compact").unwrap();
+    grid.sort_spec([("command", SortOrder::Desc)]).unwrap();
+    grid.set_overflow_policy(OverflowPolicy::Stack);
+    grid.set_key_column(command).unwrap();
+    grid.set_table_alignment(TableAlign::Center);
+    grid.config()
+}
+
+#[test]
+fn layout_round_trip() {
+    let config = layout_config();
+    let layout = &config.layout;
+    assert_eq!(layout.hidden, vec!["state"]);
+    assert_eq!(layout.shown, vec!["debug"]);
+    assert_eq!(layout.frozen, vec!["pid"]);
+    assert_eq!(
+        layout.views,
+        vec![(
+            "compact".to_string(),
+            vec!["pid".to_string(), "command".to_string()]
+        )]
+    );
+    assert_eq!(layout.active_view.as_deref(), Some("compact"));
+    assert_eq!(layout.sort, vec![("command".to_string(), SortOrder::Desc)]);
+    assert_eq!(layout.overflow, OverflowPolicy::Stack);
+    assert_eq!(layout.key_column.as_deref(), Some("command"));
+    assert_eq!(layout.table_align, TableAlign::Center);
+    assert_eq!(config.headers[4].pin(), Some(Pin::Right));
+
+    let json = serde_json::to_string(&config).unwrap();
+    let loaded: GridConfig = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded, config);
+
+    let mut grid = TTYGrid::from_config(loaded).unwrap();
+    assert_eq!(grid.config(), config);
+
+    add_line!(grid, "1", "init", "sleeping", "-", "node-a");
+    add_line!(grid, "2", "sshd", "running", "-", "node-a");
+    let output = grid.display().unwrap();
+    let lines = output.lines().map(str::trim).collect::<Vec<_>>();
+    assert_eq!(
+        lines[0].split_whitespace().collect::<Vec<_>>(),
+        vec!["pid", "command"]
+    );
+    assert_eq!(
+        lines[2].split_whitespace().collect::<Vec<_>>(),
+        vec!["2", "sshd"]
+    );
+}
+
+#[test]
+fn layout_of_unknown_columns() {
+    let mut config = config();
+    config.layout.views = vec![("compact".to_string(), vec!["size".to_string()])];
+    let err = TTYGrid::from_config(config).err().unwrap();
+    assert_eq!(err.to_string(), "no column named \"size\"");
+}
@@ -0,0 +1,59 @@
+// tests of #[derive(TtyGridRow)]: derived rows are added to a grid, and the display is compared
+// with ttygrid::assert_grid_eq!.
+#![cfg(feature = "derive")]
+
+use ttygrid::{assert_grid_eq, TTYGrid, TtyGridRow};
+
+#[derive(TtyGridRow)]
+struct Process {
+    #[ttygrid(priority = 3, align = "right")]
+    pid: u32,
+    #[ttygrid(name = "command")]
+    cmd: String,
+    #[ttygrid(skip)]
+    #[allow(dead_code)]
+    internal: bool,
+}
+
+fn processes() -> Vec<Process> {
+    vec![
+        Process {
+            pid: 1,
+            cmd: "init".to_string(),
+            internal: true,
+        },
+        Process {
+            pid: 4022,
+            cmd: "sshd".to_string(),
+            internal: false,
+        },
+    ]
+}
+
+#[test]
+fn derived_rows() {
+    let mut grid = TTYGrid::new(Process::headers()).unwrap();
+    for process in processes() {
+        let line = process.into_line(&grid).unwrap();
+        grid.add_line(line);
+    }
+
+    assert_grid_eq!(
+        grid,
+        "
+         pid       command
+        ------------------
+           1       init
+        4022       sshd
+        "
+    );
+}
+
+#[test]
+fn derived_headers() {
+    let labels = Process::headers()
+        .iter()
+        .map(|header| header.borrow().text().to_string())
+        .collect::<Vec<String>>();
+    assert_eq!(labels, vec!["pid", "command"]);
+}
@@ -0,0 +1,42 @@
+// property tests of the layout: random rows are laid out for random terminal widths, and every
+// layout is checked with ttygrid::test::check_layout.
+use proptest::prelude::*;
+use ttygrid::{header, test::FakeTerm, SafeGridHeader, TTYGrid, TerminalTooSmall};
+
+fn check(width: usize, rows: Vec<Vec<String>>) -> Result<(), TestCaseError> {
+    let columns = rows.first().map(Vec::len).unwrap_or(1);
+    let headers = (0..columns)
+        .map(|n| {
+            let label = format!("column-{}", n);
+            header!(label)
+        })
+        .collect::<Vec<SafeGridHeader>>();
+
+    let term = FakeTerm::new(width, 24);
+    let mut grid = TTYGrid::with_width_source(headers, &term).unwrap();
+    for row in rows {
+        grid.add_contents(row).unwrap();
+    }
+
+    match ttygrid::test::check_layout(&mut grid) {
+        Err(e) if e.is::<TerminalTooSmall>() => Ok(()),
+        Err(e) => Err(TestCaseError::fail(e.to_string())),
+        Ok(()) => Ok(()),
+    }
+}
+
+fn rows() -> impl Strategy<Value = Vec<Vec<String>>> {
+    (1..8usize).prop_flat_map(|columns| {
+        prop::collection::vec(
+            prop::collection::vec("[a-zA-Z0-9 ._-]{0,40}", columns),
+            0..30,
+        )
+    })
+}
+
+proptest! {
+    #[test]
+    fn layouts_fit_the_terminal(width in 10..200usize, rows in rows()) {
+        check(width, rows)?;
+    }
+}
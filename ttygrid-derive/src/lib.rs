@@ -0,0 +1,132 @@
+//! Derive macro for ttygrid's `TtyGridRow` trait. Use it through the `derive` feature of ttygrid,
+//! which re-exports it; see the documentation of `TtyGridRow` there.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitInt, LitStr};
+
+#[proc_macro_derive(TtyGridRow, attributes(ttygrid))]
+pub fn derive_tty_grid_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct FieldOptions {
+    name: Option<String>,
+    key: Option<String>,
+    priority: Option<LitInt>,
+    align: Option<String>,
+    skip: bool,
+}
+
+fn field_options(field: &syn::Field) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("ttygrid")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                options.name = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("key") {
+                options.key = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("priority") {
+                options.priority = Some(meta.value()?.parse::<LitInt>()?);
+            } else if meta.path.is_ident("align") {
+                let align = meta.value()?.parse::<LitStr>()?;
+                match align.value().as_str() {
+                    "left" | "right" | "center" | "decimal" | "unit" => {
+                        options.align = Some(align.value())
+                    }
+                    _ => return Err(syn::Error::new_spanned(
+                        align,
+                        "align must be \"left\", \"right\", \"center\", \"decimal\" or \"unit\"",
+                    )),
+                }
+            } else if meta.path.is_ident("skip") {
+                options.skip = true;
+            } else {
+                return Err(meta.error("unknown ttygrid attribute"));
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(options)
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input,
+                    "TtyGridRow can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input,
+                "TtyGridRow can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut headers = Vec::new();
+    let mut contents = Vec::new();
+
+    for field in fields {
+        let options = field_options(field)?;
+        if options.skip {
+            continue;
+        }
+
+        let ident = field.ident.as_ref().unwrap();
+        let name = options.name.unwrap_or_else(|| ident.to_string());
+
+        let mut header = quote!(::ttygrid::GridHeader::default().set_text(#name));
+        if let Some(key) = options.key {
+            header = quote!(#header.set_key(#key));
+        }
+        if let Some(priority) = options.priority {
+            header = quote!(#header.set_priority(#priority));
+        }
+        if let Some(align) = options.align {
+            let variant = match align.as_str() {
+                "right" => quote!(Right),
+                "center" => quote!(Center),
+                "decimal" => quote!(Decimal),
+                "unit" => quote!(Unit),
+                _ => quote!(Left),
+            };
+            header = quote!(#header.set_alignment(::ttygrid::Alignment::#variant));
+        }
+
+        headers.push(quote! {
+            ::std::rc::Rc::new(::std::cell::RefCell::new(#header))
+        });
+        contents.push(quote!(::std::string::ToString::to_string(&self.#ident)));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::ttygrid::TtyGridRow for #name #ty_generics #where_clause {
+            fn headers() -> ::std::vec::Vec<::ttygrid::SafeGridHeader> {
+                vec![#(#headers),*]
+            }
+
+            fn into_line(
+                &self,
+                grid: &::ttygrid::TTYGrid,
+            ) -> ::ttygrid::__private::Result<::ttygrid::GridLine> {
+                grid.line_from(vec![#(#contents),*])
+            }
+        }
+    })
+}